@@ -10,4 +10,5 @@ cd generator/
 cargo build --release
 mv target/release/vocab_generator ../generate
 cd ../
-./generate
+# The lock file must be committed, see generator/README.md
+./generate --lock daily-challenges.lock
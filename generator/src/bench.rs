@@ -0,0 +1,324 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::{
+    compress::{compress, Compression},
+    encoder::Encoder,
+    partition::{Partition, PartitionScheme},
+};
+
+/// Sizes and times of the encoders on the sub-dictionaries of a build.
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchReport {
+    /// Partitioning scheme of the sub-dictionaries.
+    pub scheme: PartitionScheme,
+    /// Number of runs of which the times are averaged.
+    pub repeat: usize,
+    /// Results of each encoder.
+    pub encoders: Vec<EncoderBench>,
+}
+
+/// Results of an encoder.
+#[derive(Clone, Debug, Serialize)]
+pub struct EncoderBench {
+    /// Name of the encoder.
+    pub encoder: String,
+    /// Size of the table of a trained encoder, in bytes.
+    pub table: usize,
+    /// Time to train the encoder on all sub-dictionaries, in microseconds.
+    pub train_time_us: f64,
+    /// Results of each sub-dictionary.
+    pub partitions: Vec<PartitionBench>,
+    /// Sum of the results of the sub-dictionaries.
+    pub total: PartitionBench,
+}
+
+/// Results of an encoder on a sub-dictionary.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PartitionBench {
+    /// Name of the sub-dictionary (e.g. `6a`).
+    pub partition: String,
+    /// Number of words.
+    pub words: usize,
+    /// Size of the encoded file, in bytes.
+    pub raw: usize,
+    /// Size of the encoded file compressed with gzip, in bytes.
+    pub gzip: usize,
+    /// Size of the encoded file compressed with brotli, in bytes.
+    pub brotli: usize,
+    /// Time to encode, in microseconds.
+    pub encode_time_us: f64,
+    /// Time to decode, in microseconds, if the encoder can list its words.
+    pub decode_time_us: Option<f64>,
+}
+
+/// Run encoders on sub-dictionaries, whose words have their prefix removed.
+///
+/// Times are averaged over `repeat` runs. Encoders that cannot encode every
+/// sub-dictionary are skipped with a warning. Exact encoders must decode each
+/// sub-dictionary back into its words, otherwise the benchmark fails.
+pub fn bench_encoders<'a, I>(
+    encoders: I,
+    sub_dictionaries: &[(Partition, Vec<&str>)],
+    scheme: PartitionScheme,
+    repeat: usize,
+) -> std::io::Result<BenchReport>
+where
+    I: IntoIterator<Item = &'a dyn Encoder>,
+{
+    let repeat = repeat.max(1);
+    let dictionaries: Vec<Vec<&str>> = sub_dictionaries
+        .iter()
+        .map(|(_, words)| words.clone())
+        .collect();
+
+    let mut results = Vec::new();
+
+    'encoders: for encoder in encoders {
+        let (trained, train_time) = time(1, || encoder.train(&dictionaries));
+        let (encoder, table) = match &trained {
+            Some((trained, table)) => (trained.as_ref(), table.len()),
+            None => (encoder, 0),
+        };
+
+        let mut partitions = Vec::new();
+        let mut total = PartitionBench {
+            partition: "total".to_owned(),
+            decode_time_us: Some(0.0),
+            ..PartitionBench::default()
+        };
+
+        for (partition, words) in sub_dictionaries.iter() {
+            let (content, encode_time) = time(repeat, || {
+                let mut content = Vec::new();
+                encoder.encode(&mut content, words).map(|_| content)
+            });
+            let content = match content {
+                Ok(content) => content,
+                Err(err) => {
+                    eprintln!("Warning: skipped encoder {}: {err}", encoder.name());
+                    continue 'encoders;
+                },
+            };
+
+            let length = partition.suffix_length();
+            let (decoded, decode_time) = time(repeat, || encoder.decode(&content, length));
+
+            // Approximate encoders (Bloom filters) cannot list their words
+            let decode_time_us = match decoded {
+                Ok(decoded) if encoder.is_exact() && decoded != *words => {
+                    return Err(bench_error(format!(
+                        "encoder {} does not decode {} into its words",
+                        encoder.name(),
+                        partition.file
+                    )));
+                },
+                Err(err) if encoder.is_exact() => {
+                    return Err(bench_error(format!(
+                        "encoder {} cannot decode {}: {err}",
+                        encoder.name(),
+                        partition.file
+                    )));
+                },
+                Ok(_) => Some(micros(decode_time)),
+                Err(_) => None,
+            };
+
+            let result = PartitionBench {
+                partition: partition.file.clone(),
+                words: words.len(),
+                raw: content.len(),
+                gzip: compress(&content, Compression::Gzip)?.len(),
+                brotli: compress(&content, Compression::Brotli)?.len(),
+                encode_time_us: micros(encode_time),
+                decode_time_us,
+            };
+
+            total.words += result.words;
+            total.raw += result.raw;
+            total.gzip += result.gzip;
+            total.brotli += result.brotli;
+            total.encode_time_us += result.encode_time_us;
+            total.decode_time_us = total
+                .decode_time_us
+                .zip(result.decode_time_us)
+                .map(|(a, b)| a + b);
+
+            partitions.push(result);
+        }
+
+        results.push(EncoderBench {
+            encoder: encoder.name().to_owned(),
+            table,
+            train_time_us: micros(train_time),
+            partitions,
+            total,
+        });
+    }
+
+    Ok(BenchReport {
+        scheme,
+        repeat,
+        encoders: results,
+    })
+}
+
+/// Run a function `repeat` times, and return its last result and its mean
+/// time.
+fn time<T, F>(repeat: usize, mut f: F) -> (T, Duration)
+where
+    F: FnMut() -> T,
+{
+    let start = Instant::now();
+    let mut result = f();
+    for _ in 1..repeat {
+        result = f();
+    }
+    (result, start.elapsed() / repeat as u32)
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
+
+fn bench_error(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Table of the results, one line per encoder and sub-dictionary, followed by
+/// the totals of each encoder.
+impl std::fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header = format!(
+            "{:<12} {:<10} {:>7} {:>9} {:>9} {:>9} {:>12} {:>12}",
+            "encoder", "partition", "words", "raw", "gzip", "brotli", "encode (µs)", "decode (µs)"
+        );
+
+        writeln!(f, "{header}")?;
+        for encoder in self.encoders.iter() {
+            for result in encoder.partitions.iter() {
+                write_row(f, &encoder.encoder, result)?;
+                writeln!(f)?;
+            }
+        }
+
+        // Totals, with the table of trained encoders
+        writeln!(f)?;
+        writeln!(f, "{header} {:>9}", "table")?;
+        for encoder in self.encoders.iter() {
+            write_row(f, &encoder.encoder, &encoder.total)?;
+            writeln!(f, " {:>9}", encoder.table)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Write a row of the table of the results, without new line.
+fn write_row(
+    f: &mut std::fmt::Formatter<'_>,
+    encoder: &str,
+    result: &PartitionBench,
+) -> std::fmt::Result {
+    write!(
+        f,
+        "{encoder:<12} {:<10} {:>7} {:>9} {:>9} {:>9} {:>12.1}",
+        result.partition,
+        result.words,
+        result.raw,
+        result.gzip,
+        result.brotli,
+        result.encode_time_us
+    )?;
+    match result.decode_time_us {
+        Some(decode_time) => write!(f, " {decode_time:>12.1}"),
+        None => write!(f, " {:>12}", "-"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        encoder::{DecodeError, EncoderRegistry},
+        partition::partition,
+    };
+
+    #[test]
+    fn sizes() {
+        let words = ["abc", "abd", "acd", "bcd"];
+        let sub_dictionaries = partition(&words, PartitionScheme::LengthLetter, 0);
+
+        let registry = EncoderRegistry::default();
+        let encoders = ["lines", "front", "bloom"].map(|name| registry.get(name).unwrap());
+        let report = bench_encoders(
+            encoders,
+            &sub_dictionaries,
+            PartitionScheme::LengthLetter,
+            2,
+        )
+        .unwrap();
+
+        let sizes: Vec<_> = report
+            .encoders
+            .iter()
+            .map(|encoder| {
+                let partitions: Vec<_> = encoder
+                    .partitions
+                    .iter()
+                    .map(|result| (result.partition.as_str(), result.words, result.raw))
+                    .collect();
+                (encoder.encoder.as_str(), partitions, encoder.total.raw)
+            })
+            .collect();
+
+        // "bc\nbd\ncd\n" and "cd\n", then "bc\nd\ncd" and "cd"
+        assert_eq!(sizes[0], ("lines", vec![("3a", 3, 9), ("3b", 1, 3)], 12));
+        assert_eq!(sizes[1], ("front", vec![("3a", 3, 7), ("3b", 1, 2)], 9));
+        assert_eq!(sizes[2].0, "bloom");
+
+        // Bloom filters cannot be decoded
+        assert!(report.encoders[1].total.decode_time_us.is_some());
+        assert!(report.encoders[2].total.decode_time_us.is_none());
+        assert!(report.to_string().starts_with("encoder"));
+    }
+
+    /// Encoder that loses the last word.
+    struct LossyEncoder;
+
+    impl Encoder for LossyEncoder {
+        fn name(&self) -> &str {
+            "lossy"
+        }
+
+        fn encode(&self, writer: &mut dyn std::io::Write, words: &[&str]) -> std::io::Result<()> {
+            for word in words.iter().take(words.len() - 1) {
+                writeln!(writer, "{word}")?;
+            }
+            Ok(())
+        }
+
+        fn decode(&self, content: &[u8], _length: usize) -> Result<Vec<String>, DecodeError> {
+            Ok(String::from_utf8_lossy(content)
+                .lines()
+                .map(str::to_owned)
+                .collect())
+        }
+    }
+
+    #[test]
+    fn broken_encoder() {
+        let words = ["abc", "abd", "acd"];
+        let sub_dictionaries = partition(&words, PartitionScheme::LengthLetter, 0);
+
+        let encoders: [&dyn Encoder; 1] = [&LossyEncoder];
+        let result = bench_encoders(
+            encoders,
+            &sub_dictionaries,
+            PartitionScheme::LengthLetter,
+            1,
+        );
+        assert!(result.is_err());
+    }
+}
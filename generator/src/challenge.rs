@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    difficulty::Difficulty,
+    lexique::{Cgram, Genre, Nombre},
+    source::Entry,
+};
+
+/// Challenge file format.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ChallengeFormat {
+    /// Text file with the solution, its display form and its difficulty tier,
+    /// one per line, which the app reads.
+    #[default]
+    Text,
+    /// JSON object with the solution, its display form and lexical metadata,
+    /// for other consumers than the app.
+    Json,
+}
+
+impl ChallengeFormat {
+    /// Get the file extension.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Challenge data.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Challenge {
+    /// Normalized solution.
+    pub solution: String,
+    /// Display form of the solution.
+    pub display: String,
+    /// Lemme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lemme: Option<String>,
+    /// Classe grammaticale
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cgram: Option<Cgram>,
+    /// Genre
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<Genre>,
+    /// Nombre
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nombre: Option<Nombre>,
+    /// Phonie
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phon: Option<String>,
+    /// Syllabation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub syll: Option<String>,
+    /// Nombre de syllabes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbsyll: Option<usize>,
+    /// Fréquence par million selon le corpus de films
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freqfilms2: Option<f64>,
+    /// Fréquence par million selon le corpus de livres
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freqlivres: Option<f64>,
+    /// Difficulty tier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// Difficulty score, between 0 (easiest) and 1 (hardest)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+}
+
+impl Challenge {
+    /// Create a challenge from its solution and display form, taking the
+    /// metadata from the most relevant entry of the solution.
+    ///
+    /// Entries spelled like the display form are preferred, then the most
+    /// frequent ones.
+    pub fn new<'a, S1, S2, I>(solution: S1, display: S2, entries: I) -> Self
+    where
+        S1: AsRef<str>,
+        S2: AsRef<str>,
+        I: IntoIterator<Item = &'a Entry>,
+    {
+        let solution = solution.as_ref();
+        let display = display.as_ref();
+
+        let key = |entry: &Entry| {
+            let frequency = entry.freqfilms2.unwrap_or(0.0) + entry.freqlivres.unwrap_or(0.0);
+            (entry.ortho.to_lowercase() == display, frequency)
+        };
+
+        let entry = entries
+            .into_iter()
+            .filter(|entry| entry.word == solution)
+            .fold(None, |best: Option<&Entry>, entry| match best {
+                Some(best) if key(best) >= key(entry) => Some(best),
+                _ => Some(entry),
+            });
+
+        let challenge = Self {
+            solution: solution.to_owned(),
+            display: display.to_owned(),
+            ..Self::default()
+        };
+
+        let Some(entry) = entry else {
+            return challenge;
+        };
+
+        let record = entry.record.as_deref();
+
+        Self {
+            lemme: entry.lemme.to_owned(),
+            cgram: entry.cgram,
+            genre: record.and_then(|record| record.genre),
+            nombre: record.and_then(|record| record.nombre),
+            phon: record.map(|record| record.phon.to_owned()),
+            syll: record.map(|record| record.syll.to_owned()),
+            nbsyll: record.map(|record| record.nbsyll),
+            freqfilms2: entry.freqfilms2,
+            freqlivres: entry.freqlivres,
+            ..challenge
+        }
+    }
+
+    /// Write the challenge to a file, given its path without extension.
+    pub fn write<P>(&self, path: P, format: ChallengeFormat) -> anyhow::Result<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().with_extension(format.extension());
+
+        let content = match format {
+            ChallengeFormat::Text => match self.difficulty {
+                Some(difficulty) => {
+                    format!("{}\n{}\n{difficulty}\n", self.solution, self.display)
+                },
+                None => format!("{}\n{}\n", self.solution, self.display),
+            },
+            ChallengeFormat::Json => serde_json::to_string(self)?,
+        };
+
+        std::fs::write(&path, content)?;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexique::Record;
+
+    fn entry(ortho: &str, cgram: Cgram, frequency: f64) -> Entry {
+        let record = Record {
+            ortho: ortho.to_owned(),
+            cgram: Some(cgram),
+            nbsyll: 2,
+            ..Record::default()
+        };
+        Entry {
+            word: "pecher".to_owned(),
+            cgram: Some(cgram),
+            freqfilms2: Some(frequency),
+            record: Some(Box::new(record)),
+            ..Entry::new(ortho)
+        }
+    }
+
+    #[test]
+    fn choose_entry() {
+        let entries = [
+            entry("pécher", Cgram::Ver, 3.0),
+            entry("pêcher", Cgram::Nom, 1.0),
+            entry("pêcher", Cgram::Ver, 2.0),
+        ];
+        let challenge = Challenge::new("pecher", "pêcher", &entries);
+
+        assert_eq!(challenge.cgram, Some(Cgram::Ver));
+        assert_eq!(challenge.freqfilms2, Some(2.0));
+        assert_eq!(challenge.nbsyll, Some(2));
+    }
+
+    #[test]
+    fn serialize_json() {
+        let challenge = Challenge {
+            solution: "eleve".to_owned(),
+            display: "élève".to_owned(),
+            cgram: Some(Cgram::Nom),
+            genre: Some(Genre::M),
+            ..Challenge::default()
+        };
+
+        assert_eq!(
+            serde_json::to_string(&challenge).unwrap(),
+            r#"{"solution":"eleve","display":"élève","cgram":"NOM","genre":"m"}"#
+        );
+    }
+}
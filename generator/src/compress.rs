@@ -0,0 +1,249 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+/// Compression of the precompressed siblings of the generated files.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// `.gz` sibling of every file.
+    Gzip,
+    /// `.br` sibling of every file.
+    Brotli,
+    /// `.zst` sibling of every sub-dictionary, compressed with a dictionary
+    /// trained on all sub-dictionaries.
+    Zstd,
+}
+
+impl Compression {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Brotli => "brotli",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Extension appended to the name of the compressed file.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Brotli => "br",
+            Self::Zstd => "zst",
+        }
+    }
+}
+
+impl std::fmt::Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Name of the zstd dictionary file, in the dictionary directory.
+pub const ZSTD_DICTIONARY: &str = "zstd.dict";
+
+/// Writer of the precompressed siblings of the generated files, which keeps
+/// track of their sizes.
+#[derive(Debug, Default)]
+pub struct Precompressor {
+    compressions: Vec<Compression>,
+    /// Dictionary trained for zstd.
+    zstd_dictionary: Option<Vec<u8>>,
+    /// Sizes of the files, by group (e.g. "dictionary").
+    sizes: BTreeMap<&'static str, GroupSizes>,
+}
+
+/// Sizes of a group of files.
+#[derive(Clone, Debug, Default)]
+struct GroupSizes {
+    num_files: usize,
+    raw: usize,
+    compressed: BTreeMap<Compression, usize>,
+}
+
+impl Precompressor {
+    pub fn new(compressions: &[Compression]) -> Self {
+        Self {
+            compressions: compressions.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    /// Train the zstd dictionary on files and write it in a directory, if
+    /// zstd is enabled.
+    ///
+    /// Without enough data to train a dictionary, files are compressed without
+    /// dictionary. Return whether a dictionary was written.
+    pub fn train_zstd<P>(
+        &mut self,
+        paths: &[PathBuf],
+        dir: P,
+        max_size: usize,
+    ) -> std::io::Result<bool>
+    where
+        P: AsRef<Path>,
+    {
+        if !self.compressions.contains(&Compression::Zstd) {
+            return Ok(false);
+        }
+
+        let samples = paths
+            .iter()
+            .map(std::fs::read)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match zstd::dict::from_samples(&samples, max_size) {
+            Ok(dictionary) => {
+                std::fs::write(dir.as_ref().join(ZSTD_DICTIONARY), &dictionary)?;
+                self.add_size("zstd dictionary", dictionary.len(), &[]);
+                self.zstd_dictionary = Some(dictionary);
+                Ok(true)
+            },
+            Err(err) => {
+                eprintln!("Warning: cannot train the zstd dictionary: {err}");
+                Ok(false)
+            },
+        }
+    }
+
+    /// Write the compressed siblings of a file (e.g. `6a.txt.gz`).
+    ///
+    /// zstd only applies to the `dictionary` and `dictionary pack` groups.
+    pub fn compress<P>(&mut self, group: &'static str, path: P) -> std::io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let mut sizes = Vec::new();
+
+        for compression in self.compressions.clone() {
+            if compression == Compression::Zstd
+                && !matches!(group, "dictionary" | "dictionary pack")
+            {
+                continue;
+            }
+
+            let compressed = match compression {
+                Compression::Zstd => compress_zstd(&data, self.zstd_dictionary.as_deref())?,
+                _ => compress(&data, compression)?,
+            };
+
+            let mut sibling = path.as_os_str().to_owned();
+            sibling.push(format!(".{}", compression.extension()));
+            std::fs::write(sibling, &compressed)?;
+
+            sizes.push((compression, compressed.len()));
+        }
+
+        self.add_size(group, data.len(), &sizes);
+
+        Ok(())
+    }
+
+    fn add_size(&mut self, group: &'static str, raw: usize, compressed: &[(Compression, usize)]) {
+        let sizes = self.sizes.entry(group).or_default();
+        sizes.num_files += 1;
+        sizes.raw += raw;
+        for (compression, size) in compressed {
+            *sizes.compressed.entry(*compression).or_default() += size;
+        }
+    }
+}
+
+/// Report of the sizes of each variant, in bytes.
+impl std::fmt::Display for Precompressor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} {:>6} {:>10}", "files", "count", "raw")?;
+        for compression in self.compressions.iter() {
+            write!(f, " {:>10}", compression.as_str())?;
+        }
+        writeln!(f)?;
+
+        for (group, sizes) in self.sizes.iter() {
+            write!(f, "{group:<16} {:>6} {:>10}", sizes.num_files, sizes.raw)?;
+            for compression in self.compressions.iter() {
+                match sizes.compressed.get(compression) {
+                    Some(size) => write!(f, " {size:>10}")?,
+                    None => write!(f, " {:>10}", "-")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compress data at the best level (zstd without dictionary).
+pub fn compress(data: &[u8], compression: Compression) -> std::io::Result<Vec<u8>> {
+    match compression {
+        Compression::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+            encoder.write_all(data)?;
+            encoder.finish()
+        },
+        Compression::Brotli => {
+            let mut compressed = Vec::new();
+            {
+                let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+                encoder.write_all(data)?;
+                encoder.flush()?;
+            }
+            Ok(compressed)
+        },
+        Compression::Zstd => compress_zstd(data, None),
+    }
+}
+
+/// Compress data with zstd, at the best level.
+fn compress_zstd(data: &[u8], dictionary: Option<&[u8]>) -> std::io::Result<Vec<u8>> {
+    let level = zstd::compression_level_range().end().to_owned();
+    match dictionary {
+        Some(dictionary) => {
+            zstd::bulk::Compressor::with_dictionary(level, dictionary)?.compress(data)
+        },
+        None => zstd::bulk::compress(data, level),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let data = "abaissa\nabaissai\nabaissaient\nabaissais\nabaissait\n".repeat(10);
+        let data = data.as_bytes();
+
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(&compress(data, Compression::Gzip).unwrap()[..])
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, data);
+
+        let mut decompressed = Vec::new();
+        brotli::Decompressor::new(&compress(data, Compression::Brotli).unwrap()[..], 4096)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, data);
+
+        let dictionary = b"abaissaient";
+        let compressed = compress_zstd(data, Some(dictionary)).unwrap();
+        let decompressed = zstd::bulk::Decompressor::with_dictionary(dictionary)
+            .unwrap()
+            .decompress(&compressed, data.len())
+            .unwrap();
+        assert_eq!(decompressed, data);
+    }
+}
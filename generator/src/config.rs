@@ -0,0 +1,500 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    challenge::ChallengeFormat,
+    compress::Compression,
+    difficulty::DifficultyWeights,
+    lexique::{Cgram, Infover},
+    partition::PartitionScheme,
+    schedule::DifficultyCurve,
+    source::{Merge, SourceConfig},
+    transliterate::LigaturePolicy,
+};
+
+/// Build configuration.
+///
+/// The configuration can be loaded from a TOML file, whose keys are the field
+/// names. Missing keys take their default value.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Path to the word database, used if `sources` is empty.
+    pub database_path: PathBuf,
+    /// Word sources.
+    pub sources: Vec<SourceConfig>,
+    /// Method to merge the word sources.
+    pub merge: Merge,
+    /// Whether to fail on the first row of a source that cannot be parsed,
+    /// instead of skipping it.
+    pub strict: bool,
+    /// Number of examples of skipped rows to report.
+    pub skipped_row_examples: usize,
+    /// Transliteration of characters, which takes precedence over the default
+    /// rules (e.g. `"ø" = "o"`).
+    pub transliteration: BTreeMap<char, String>,
+    /// Policy for ligatures (e.g. "œ").
+    pub ligatures: LigaturePolicy,
+    /// Dictionary encoding method.
+    pub encoder_name: String,
+    /// Compression applied before comparing the sizes of the encodings of the
+    /// `auto` encoder, if any.
+    pub auto_compression: Option<Compression>,
+    /// Number of words per block of the `frontblock` encoder.
+    pub front_block_size: usize,
+    /// False positive rate of the `bloom` encoder.
+    pub false_positive_rate: f64,
+    /// Compressions of the precompressed siblings of the generated files.
+    pub compression: Vec<Compression>,
+    /// Maximum size of the zstd dictionary, in bytes.
+    pub zstd_dictionary_size: usize,
+    /// Output directory.
+    pub output_path: PathBuf,
+    /// Directory for the challenges, relative to the output directory.
+    pub challenge_dir: PathBuf,
+    /// Directory for the dictionaries, relative to the output directory.
+    pub dictionary_dir: PathBuf,
+    /// Whether to write challenges.
+    pub write_challenge: bool,
+    /// Challenge file format.
+    pub challenge_format: ChallengeFormat,
+    /// Whether to write dictionaries.
+    pub write_dictionary: bool,
+    /// Whether to write the dictionaries into a single pack instead of a
+    /// directory.
+    pub pack_dictionary: bool,
+    /// Method to split the dictionary into sub-dictionaries.
+    pub partition: PartitionScheme,
+    /// Maximum number of words per sub-dictionary of the `balanced`
+    /// partitioning scheme.
+    pub partition_size: usize,
+    /// Key of the random number generator.
+    pub random_seed: u64,
+    /// Minimum word length.
+    pub min_length: usize,
+    /// Maximum word length.
+    pub max_length: usize,
+    /// Allowed grammatical categories in dictionary.
+    pub dictionary_cgram: Vec<Cgram>,
+    /// Allowed grammatical categories in challenges.
+    pub challenge_cgram: Vec<Cgram>,
+    /// Allowed verbal forms in challenges (e.g. `"inf"`, `"par:pre"`).
+    /// Verbs are kept if one of their forms matches one of the patterns.
+    /// An empty list keeps all verbs.
+    pub challenge_infover: Vec<Infover>,
+    /// Minimum frequency per million in movies for challenges.
+    pub challenge_min_freqfilms2: f64,
+    /// Minimum frequency per million in books for challenges.
+    pub challenge_min_freqlivres: f64,
+    /// Weights of the features in the difficulty score of challenges.
+    pub difficulty_weights: DifficultyWeights,
+    /// Fractions of the challenges, sorted by difficulty score, below which
+    /// challenges are easy and medium. The others are hard.
+    pub difficulty_thresholds: [f64; 2],
+    /// Number of daily challenges to generate, if `end_date` is not set.
+    pub num_daily_challenges: usize,
+    /// First date of the daily schedule (launch date).
+    /// Dates are always drawn from this date, so that the word of a date does
+    /// not depend on `start_date` nor on the day of the build.
+    #[serde(deserialize_with = "deserialize_date")]
+    pub launch_date: NaiveDate,
+    /// First daily challenge to write (today by default).
+    #[serde(deserialize_with = "deserialize_optional_date")]
+    pub start_date: Option<NaiveDate>,
+    /// Last daily challenge to write.
+    #[serde(deserialize_with = "deserialize_optional_date")]
+    pub end_date: Option<NaiveDate>,
+    /// Path to the lock file of published daily challenges.
+    pub lock_path: Option<PathBuf>,
+    /// Last published daily challenge (today by default). Only daily
+    /// challenges up to this date are recorded in the lock file.
+    #[serde(deserialize_with = "deserialize_optional_date")]
+    pub published_through: Option<NaiveDate>,
+    /// Minimum number of days between two daily challenges with the same word.
+    pub daily_repeat_interval: usize,
+    /// Minimum number of days between two daily challenges with the same lemma.
+    pub daily_lemma_interval: usize,
+    /// Whether consecutive daily challenges must start with different letters.
+    pub daily_distinct_first_letter: bool,
+    /// Target difficulty of the daily challenges of each weekday.
+    pub daily_difficulty: DifficultyCurve,
+}
+
+impl Config {
+    /// Load a configuration from a TOML file.
+    pub fn from_file<P>(path: P) -> Result<Self, ConfigError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| ConfigError(format!("cannot read {path:?}: {err}")))?;
+        toml::from_str(&content).map_err(|err| ConfigError(format!("in {path:?}: {err}")))
+    }
+
+    /// Check that the parameters are consistent.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.min_length == 0 {
+            return Err(ConfigError("min_length must be at least 1".to_owned()));
+        }
+
+        if self.min_length > self.max_length {
+            let (min, max) = (self.min_length, self.max_length);
+            return Err(ConfigError(format!(
+                "min_length ({min}) must not be greater than max_length ({max})"
+            )));
+        }
+
+        if self.dictionary_cgram.is_empty() {
+            return Err(ConfigError("dictionary_cgram must not be empty".to_owned()));
+        }
+
+        // Challenges are a subset of the dictionary
+        if let Some(cgram) = self
+            .challenge_cgram
+            .iter()
+            .find(|cgram| !self.dictionary_cgram.contains(cgram))
+        {
+            return Err(ConfigError(format!(
+                "challenge_cgram contains {cgram:?}, which is not in dictionary_cgram"
+            )));
+        }
+
+        // Words are lowercased before their characters are looked up
+        if let Some(c) = self
+            .transliteration
+            .keys()
+            .find(|c| !c.to_lowercase().eq(std::iter::once(**c)))
+        {
+            return Err(ConfigError(format!(
+                "transliteration contains {c:?}, which is not lowercase"
+            )));
+        }
+
+        for (name, value) in [
+            ("challenge_min_freqfilms2", self.challenge_min_freqfilms2),
+            ("challenge_min_freqlivres", self.challenge_min_freqlivres),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(ConfigError(format!(
+                    "{name} must be a non-negative number, got {value}"
+                )));
+            }
+        }
+
+        if self.front_block_size == 0 {
+            return Err(ConfigError(
+                "front_block_size must be at least 1".to_owned(),
+            ));
+        }
+
+        if self.partition_size == 0 {
+            return Err(ConfigError("partition_size must be at least 1".to_owned()));
+        }
+
+        if !(self.false_positive_rate > 0.0 && self.false_positive_rate < 1.0) {
+            return Err(ConfigError(format!(
+                "false_positive_rate must be between 0 and 1 (exclusive), got {}",
+                self.false_positive_rate
+            )));
+        }
+
+        self.difficulty_weights
+            .validate()
+            .map_err(|err| ConfigError(format!("difficulty_weights: {err}")))?;
+
+        let [easy, medium] = self.difficulty_thresholds;
+        if !(0.0..=1.0).contains(&easy) || !(easy..=1.0).contains(&medium) {
+            return Err(ConfigError(format!(
+                "difficulty_thresholds must be increasing fractions between 0 and 1, got [{easy}, {medium}]"
+            )));
+        }
+
+        if let (Some(start_date), Some(end_date)) = (self.start_date, self.end_date) {
+            if start_date > end_date {
+                return Err(ConfigError(format!(
+                    "start_date ({start_date}) must not be after end_date ({end_date})"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            database_path: "Lexique383.tsv".into(),
+            sources: Vec::new(),
+            merge: Merge::default(),
+            strict: false,
+            skipped_row_examples: 5,
+            transliteration: BTreeMap::new(),
+            ligatures: LigaturePolicy::default(),
+            encoder_name: "front".to_owned(),
+            auto_compression: None,
+            front_block_size: 16,
+            false_positive_rate: 0.01,
+            compression: Vec::new(),
+            zstd_dictionary_size: 4096,
+            output_path: "public".into(),
+            challenge_dir: "challenge".into(),
+            dictionary_dir: "dictionary".into(),
+            write_challenge: true,
+            challenge_format: ChallengeFormat::default(),
+            write_dictionary: true,
+            pack_dictionary: false,
+            partition: PartitionScheme::default(),
+            partition_size: 256,
+            random_seed: 0x548c9decbce65297,
+            min_length: 6,
+            max_length: 10,
+            dictionary_cgram: vec![Cgram::Adj, Cgram::Adv, Cgram::Nom, Cgram::Ver],
+            challenge_cgram: vec![Cgram::Nom, Cgram::Ver],
+            challenge_infover: ["inf", "par:pre", "par:pas"]
+                .into_iter()
+                .map(|s| s.parse().unwrap())
+                .collect(),
+            challenge_min_freqfilms2: 1.0,
+            challenge_min_freqlivres: 1.0,
+            difficulty_weights: DifficultyWeights::default(),
+            difficulty_thresholds: [0.4, 0.8],
+            num_daily_challenges: 365 * 2,
+            launch_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            start_date: None,
+            end_date: None,
+            lock_path: None,
+            published_through: None,
+            daily_repeat_interval: 365,
+            daily_lemma_interval: 30,
+            daily_distinct_first_letter: true,
+            daily_difficulty: DifficultyCurve::default(),
+        }
+    }
+}
+
+/// Deserialize an optional date, written either as a TOML date or as a string.
+fn deserialize_optional_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_date(deserializer).map(Some)
+}
+
+/// Deserialize a date, written either as a TOML date or as a string.
+fn deserialize_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Date {
+        Toml(toml::value::Datetime),
+        String(String),
+    }
+
+    let s = match Date::deserialize(deserializer)? {
+        Date::Toml(date) => date.to_string(),
+        Date::String(s) => s,
+    };
+
+    NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+        .map_err(|err| serde::de::Error::custom(format!("invalid date {s:?}: {err}")))
+}
+
+/// Configuration error.
+#[derive(Debug)]
+pub struct ConfigError(String);
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid configuration: {}", self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+
+    #[test]
+    fn default_is_valid() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn parse_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            min_length = 5
+            max_length = 8
+            dictionary_cgram = ["NOM", "VER", "ADJ"]
+            challenge_cgram = ["VER"]
+            challenge_infover = ["inf"]
+            challenge_min_freqfilms2 = 2.5
+            ligatures = "reject"
+
+            [transliteration]
+            "ø" = "o"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.min_length, 5);
+        assert_eq!(config.max_length, 8);
+        assert_eq!(
+            config.dictionary_cgram,
+            [Cgram::Nom, Cgram::Ver, Cgram::Adj]
+        );
+        assert_eq!(config.challenge_cgram, [Cgram::Ver]);
+        assert_eq!(config.challenge_infover, ["inf".parse().unwrap()]);
+        assert_eq!(config.challenge_min_freqfilms2, 2.5);
+        assert_eq!(config.challenge_min_freqlivres, 1.0);
+        assert_eq!(config.ligatures, LigaturePolicy::Reject);
+        assert_eq!(config.transliteration.get(&'ø').unwrap(), "o");
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn parse_dates() {
+        let config: Config = toml::from_str(
+            r#"
+            launch_date = 2024-01-01
+            start_date = "2024-02-01"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.launch_date,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
+        assert_eq!(config.start_date, NaiveDate::from_ymd_opt(2024, 2, 1));
+        assert_eq!(config.end_date, None);
+        assert!(toml::from_str::<Config>("end_date = 2024-01-01T00:00:00").is_err());
+    }
+
+    #[test]
+    fn unknown_cgram() {
+        let result = toml::from_str::<Config>(r#"dictionary_cgram = ["NOM", "FOO"]"#);
+        assert!(result.unwrap_err().to_string().contains("FOO"));
+    }
+
+    #[test]
+    fn unknown_key() {
+        assert!(toml::from_str::<Config>("min_lenght = 5").is_err());
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        let config = Config {
+            min_length: 8,
+            max_length: 6,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn uppercase_transliteration() {
+        let config: Config = toml::from_str("transliteration = { \"Œ\" = \"oe\" }").unwrap();
+        assert!(config.validate().is_err());
+
+        let config: Config = toml::from_str("transliteration = { \"œ\" = \"oe\" }").unwrap();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn challenge_cgram_not_in_dictionary() {
+        let config = Config {
+            dictionary_cgram: vec![Cgram::Nom],
+            challenge_cgram: vec![Cgram::Nom, Cgram::Ver],
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn invalid_false_positive_rate() {
+        for false_positive_rate in [0.0, 1.0, f64::NAN] {
+            let config = Config {
+                false_positive_rate,
+                ..Config::default()
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn parse_partition() {
+        let config: Config = toml::from_str(
+            r#"
+            partition = "balanced"
+            partition_size = 100
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.partition, PartitionScheme::Balanced);
+        assert_eq!(config.partition_size, 100);
+        assert!(config.validate().is_ok());
+        assert!(toml::from_str::<Config>(r#"partition = "letter""#).is_err());
+
+        let config = Config {
+            partition_size: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn parse_auto_compression() {
+        let config: Config = toml::from_str(
+            r#"
+            encoder_name = "auto"
+            auto_compression = "brotli"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.auto_compression, Some(Compression::Brotli));
+        assert_eq!(Config::default().auto_compression, None);
+    }
+
+    #[test]
+    fn parse_difficulty() {
+        let config: Config = toml::from_str(
+            r#"
+            difficulty_weights = { old20 = 0.0, deflem = 2.0 }
+            difficulty_thresholds = [0.5, 0.9]
+
+            [daily_difficulty]
+            monday = "easy"
+            sunday = "hard"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.difficulty_weights.old20, 0.0);
+        assert_eq!(config.difficulty_weights.deflem, 2.0);
+        assert_eq!(config.difficulty_weights.freqfilms2, 1.0);
+        assert_eq!(config.daily_difficulty.monday, Some(Difficulty::Easy));
+        assert_eq!(config.daily_difficulty.tuesday, None);
+        assert_eq!(config.daily_difficulty.sunday, Some(Difficulty::Hard));
+        assert!(config.validate().is_ok());
+
+        let config = Config {
+            difficulty_thresholds: [0.8, 0.4],
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
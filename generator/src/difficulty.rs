@@ -0,0 +1,335 @@
+use serde::{Deserialize, Serialize};
+
+use crate::source::Entry;
+
+/// Number of features of the difficulty model.
+const NUM_FEATURES: usize = 6;
+
+/// Difficulty tier of a challenge.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// All tiers, from the easiest to the hardest.
+    pub const ALL: [Self; 3] = [Self::Easy, Self::Medium, Self::Hard];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+        }
+    }
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Weights of the features in the difficulty score.
+///
+/// A weight of zero ignores the feature.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifficultyWeights {
+    /// Rare words in movies are harder.
+    pub freqfilms2: f64,
+    /// Rare words in books are harder.
+    pub freqlivres: f64,
+    /// Words far from other words (high OLD20) are harder.
+    pub old20: f64,
+    /// Words with few orthographic neighbours are harder.
+    pub voisorth: f64,
+    /// Words with a late orthographic uniqueness point are harder.
+    pub puorth: f64,
+    /// Words whose lemma is known by few people are harder.
+    pub deflem: f64,
+}
+
+impl DifficultyWeights {
+    fn values(&self) -> [f64; NUM_FEATURES] {
+        [
+            self.freqfilms2,
+            self.freqlivres,
+            self.old20,
+            self.voisorth,
+            self.puorth,
+            self.deflem,
+        ]
+    }
+
+    /// Check that the weights are non-negative and not all zero.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(weight) = self
+            .values()
+            .into_iter()
+            .find(|weight| !weight.is_finite() || *weight < 0.0)
+        {
+            return Err(format!(
+                "weights must be non-negative numbers, got {weight}"
+            ));
+        }
+
+        if self.values().iter().sum::<f64>() <= 0.0 {
+            return Err("at least one weight must be positive".to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for DifficultyWeights {
+    fn default() -> Self {
+        Self {
+            freqfilms2: 1.0,
+            freqlivres: 1.0,
+            old20: 0.5,
+            voisorth: 0.5,
+            puorth: 0.25,
+            deflem: 1.0,
+        }
+    }
+}
+
+/// Features of a word used to score its difficulty.
+///
+/// Unknown features are `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Features {
+    pub freqfilms2: Option<f64>,
+    pub freqlivres: Option<f64>,
+    pub old20: Option<f64>,
+    pub voisorth: Option<f64>,
+    pub puorth: Option<f64>,
+    pub deflem: Option<f64>,
+}
+
+impl Features {
+    /// Get the features of a word from its entries.
+    ///
+    /// Frequencies are summed over the entries (e.g. the noun and the verb
+    /// "pêcher"), the orthographic features are taken from the most frequent
+    /// entry, and the best known lemma is used.
+    pub fn from_entries<'a, I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Entry>,
+    {
+        let entries: Vec<&Entry> = entries.into_iter().collect();
+
+        let sum = |f: fn(&Entry) -> Option<f64>| {
+            entries
+                .iter()
+                .filter_map(|entry| f(entry))
+                .reduce(|a, b| a + b)
+        };
+
+        let frequency =
+            |entry: &Entry| entry.freqfilms2.unwrap_or(0.0) + entry.freqlivres.unwrap_or(0.0);
+
+        let record = entries
+            .iter()
+            .filter(|entry| entry.record.is_some())
+            .max_by(|a, b| frequency(a).total_cmp(&frequency(b)))
+            .and_then(|entry| entry.record.as_deref());
+
+        Self {
+            freqfilms2: sum(|entry| entry.freqfilms2),
+            freqlivres: sum(|entry| entry.freqlivres),
+            old20: record.map(|record| record.old20),
+            voisorth: record.map(|record| record.voisorth as f64),
+            puorth: record.map(|record| record.puorth as f64),
+            deflem: entries
+                .iter()
+                .filter_map(|entry| entry.record.as_ref()?.deflem)
+                .max_by(f64::total_cmp),
+        }
+    }
+
+    /// Get the features, oriented so that higher values are harder.
+    fn values(&self) -> [Option<f64>; NUM_FEATURES] {
+        [
+            self.freqfilms2.map(|v| -v),
+            self.freqlivres.map(|v| -v),
+            self.old20,
+            self.voisorth.map(|v| -v),
+            self.puorth,
+            self.deflem.map(|v| -v),
+        ]
+    }
+}
+
+/// Score the difficulty of words, between 0 (easiest) and 1 (hardest).
+///
+/// Each feature is converted to its percentile rank among the words, so that
+/// features of different scales can be combined. The score is the weighted
+/// mean of the ranks of the known features, or 0.5 if no feature is known.
+pub fn difficulty_scores(features: &[Features], weights: &DifficultyWeights) -> Vec<f64> {
+    let values: Vec<[Option<f64>; NUM_FEATURES]> = features.iter().map(Features::values).collect();
+    let weights = weights.values();
+
+    // Weighted sum of the ranks and sum of the weights of each word
+    let mut sums = vec![(0.0, 0.0); features.len()];
+
+    for (feature, weight) in weights.into_iter().enumerate() {
+        if weight == 0.0 {
+            continue;
+        }
+
+        let column: Vec<Option<f64>> = values.iter().map(|v| v[feature]).collect();
+
+        for ((sum, total_weight), rank) in sums.iter_mut().zip(percentile_ranks(&column)) {
+            if let Some(rank) = rank {
+                *sum += weight * rank;
+                *total_weight += weight;
+            }
+        }
+    }
+
+    sums.into_iter()
+        .map(|(sum, total_weight)| {
+            if total_weight > 0.0 {
+                sum / total_weight
+            } else {
+                0.5
+            }
+        })
+        .collect()
+}
+
+/// Assign tiers to scored words.
+///
+/// Words are sorted by score: the first `thresholds[0]` fraction of the words
+/// is easy, the words up to the `thresholds[1]` fraction are medium, and the
+/// others are hard. Equal scores are ordered by index.
+pub fn difficulty_tiers(scores: &[f64], thresholds: [f64; 2]) -> Vec<Difficulty> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[a].total_cmp(&scores[b]).then(a.cmp(&b)));
+
+    let n = scores.len() as f64;
+    let mut tiers = vec![Difficulty::Medium; scores.len()];
+
+    for (position, index) in order.into_iter().enumerate() {
+        let position = position as f64;
+        tiers[index] = if position < thresholds[0] * n {
+            Difficulty::Easy
+        } else if position < thresholds[1] * n {
+            Difficulty::Medium
+        } else {
+            Difficulty::Hard
+        };
+    }
+
+    tiers
+}
+
+/// Compute the percentile rank of values, between 0 and 1.
+///
+/// Equal values share their mean rank. A single known value has rank 0.5.
+fn percentile_ranks(values: &[Option<f64>]) -> Vec<Option<f64>> {
+    let mut known: Vec<(f64, usize)> = values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| value.map(|value| (value, index)))
+        .collect();
+    known.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut ranks = vec![None; values.len()];
+
+    if known.len() < 2 {
+        if let [(_, index)] = known[..] {
+            ranks[index] = Some(0.5);
+        }
+        return ranks;
+    }
+
+    let last = (known.len() - 1) as f64;
+
+    let mut start = 0;
+
+    while start < known.len() {
+        // Equal values are in [start, end)
+        let end = start
+            + known[start..]
+                .iter()
+                .take_while(|(value, _)| *value == known[start].0)
+                .count();
+        let rank = (start + end - 1) as f64 / 2.0 / last;
+        for (_, index) in &known[start..end] {
+            ranks[*index] = Some(rank);
+        }
+        start = end;
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks() {
+        assert_eq!(
+            percentile_ranks(&[Some(3.0), None, Some(1.0), Some(3.0), Some(2.0)]),
+            [
+                Some(5.0 / 6.0),
+                None,
+                Some(0.0),
+                Some(5.0 / 6.0),
+                Some(1.0 / 3.0)
+            ]
+        );
+        assert_eq!(percentile_ranks(&[None, Some(1.0)]), [None, Some(0.5)]);
+    }
+
+    #[test]
+    fn scores_and_tiers() {
+        let features = |frequency: f64, deflem: Option<f64>| Features {
+            freqfilms2: Some(frequency),
+            freqlivres: Some(frequency),
+            deflem,
+            ..Features::default()
+        };
+        let features = [
+            features(100.0, Some(100.0)),
+            features(1.0, Some(50.0)),
+            features(10.0, None),
+            features(50.0, Some(90.0)),
+            features(2.0, Some(60.0)),
+        ];
+
+        let scores = difficulty_scores(&features, &DifficultyWeights::default());
+        assert_eq!(scores[0], 0.0);
+        assert_eq!(scores[1], 1.0);
+
+        assert_eq!(
+            difficulty_tiers(&scores, [0.4, 0.8]),
+            [
+                Difficulty::Easy,
+                Difficulty::Hard,
+                Difficulty::Medium,
+                Difficulty::Easy,
+                Difficulty::Medium
+            ]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::source::Entry;
+
+/// Map normalized words to their display form (e.g. "eleve" to "élève").
+///
+/// When several spellings have the same normalized form (e.g. "pêcher" and
+/// "pécher"), the most frequent spelling is chosen, summing the frequencies in
+/// movies and books over all entries. Ties are broken by alphabetical order.
+pub fn display_forms<'a, I>(entries: I) -> HashMap<&'a str, String>
+where
+    I: IntoIterator<Item = &'a Entry>,
+{
+    // Total frequency of each spelling, by normalized word
+    let mut frequencies: BTreeMap<&str, BTreeMap<String, f64>> = BTreeMap::new();
+
+    for entry in entries {
+        let frequency = entry.freqfilms2.unwrap_or(0.0) + entry.freqlivres.unwrap_or(0.0);
+        *frequencies
+            .entry(&entry.word)
+            .or_default()
+            .entry(entry.ortho.to_lowercase())
+            .or_default() += frequency;
+    }
+
+    frequencies
+        .into_iter()
+        .filter_map(|(word, spellings)| {
+            // Iterate in reverse order, so that the first spelling wins ties
+            spellings
+                .into_iter()
+                .rev()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(ortho, _)| (word, ortho))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, ortho: &str, frequency: f64) -> Entry {
+        Entry {
+            word: word.to_owned(),
+            freqfilms2: Some(frequency),
+            freqlivres: Some(frequency),
+            ..Entry::new(ortho)
+        }
+    }
+
+    #[test]
+    fn most_frequent_spelling() {
+        let entries = [
+            entry("pecher", "pécher", 2.0),
+            entry("pecher", "pêcher", 1.5),
+            entry("pecher", "pêcher", 1.0),
+            entry("eleve", "élève", 0.0),
+            entry("eleve", "élevé", 0.0),
+            entry("maison", "maison", 10.0),
+        ];
+        let forms = display_forms(&entries);
+
+        assert_eq!(forms["pecher"], "pêcher");
+        assert_eq!(forms["eleve"], "élevé");
+        assert_eq!(forms["maison"], "maison");
+    }
+}
@@ -1,19 +1,239 @@
-use std::{
-    collections::BTreeMap,
-    fs::File,
-    io::{BufWriter, Write},
-    path::Path,
-};
-
-/// Write a list of words line by line, without compression (for benchmarking).
-pub fn write_lines<P, I, T>(path: P, iter: I) -> Result<(), std::io::Error>
+mod blocked;
+mod bloom;
+mod dawg;
+mod huffman;
+
+use std::{collections::BTreeMap, io::Write};
+
+pub use blocked::BlockedFrontCodingEncoder;
+pub use bloom::BloomEncoder;
+pub use dawg::DawgEncoder;
+pub use huffman::HuffmanEncoder;
+
+/// Name given to `--encoder` to pick the smallest encoding of each
+/// sub-dictionary among the exact encoders of a registry.
+pub const AUTO_ENCODER: &str = "auto";
+
+/// Method to encode a sub-dictionary.
+///
+/// Words are given without their first letter, sorted and without duplicates.
+/// They usually have the same length, but `lines`, `front`, `frontblock`,
+/// `trie`, `dawg` and `bloom` also accept words of different lengths, in which
+/// case the length given to the decoder is the maximum length of the words.
+pub trait Encoder {
+    /// Name of the encoder, as given to `--encoder`.
+    fn name(&self) -> &str;
+
+    /// Extension of the encoded files.
+    fn extension(&self) -> &str {
+        "txt"
+    }
+
+    /// Whether decoding gives back the encoded words exactly.
+    ///
+    /// Approximate encoders (e.g. `bloom`) are not picked by the `auto`
+    /// encoder.
+    fn is_exact(&self) -> bool {
+        true
+    }
+
+    /// Train the encoder on all the sub-dictionaries of a build.
+    ///
+    /// Encoders that share data between the sub-dictionaries (e.g. a code
+    /// table) return the trained encoder and the shared data, which is written
+    /// once in a `table` file and given to [`Encoder::load`] when decoding.
+    fn train(&self, _dictionaries: &[Vec<&str>]) -> Option<(Box<dyn Encoder>, Vec<u8>)> {
+        None
+    }
+
+    /// Load the data written by [`Encoder::train`].
+    fn load(&self, _data: &[u8]) -> Result<Box<dyn Encoder>, DecodeError> {
+        Err(DecodeError::new(format!(
+            "the {} encoder has no table",
+            self.name()
+        )))
+    }
+
+    /// Encode a list of words into a writer.
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error>;
+
+    /// Decode a list of words, given their (maximum) length.
+    fn decode(&self, content: &[u8], length: usize) -> Result<Vec<String>, DecodeError>;
+
+    /// Check whether a word is in an encoded list of words of a given length.
+    ///
+    /// By default, the list is decoded and searched.
+    fn contains(&self, content: &[u8], length: usize, word: &str) -> Result<bool, DecodeError> {
+        let words = self.decode(content, length)?;
+        Ok(words.binary_search_by(|w| w.as_str().cmp(word)).is_ok())
+    }
+}
+
+/// Set of encoders, identified by name.
+///
+/// The default registry contains the built-in encoders (`lines`, `front`,
+/// `frontopt`, `frontblock`, `trie`, `dawg`, `huffman` and `bloom`).
+pub struct EncoderRegistry {
+    encoders: Vec<Box<dyn Encoder>>,
+}
+
+impl EncoderRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            encoders: Vec::new(),
+        }
+    }
+
+    /// Register an encoder, replacing any encoder with the same name.
+    pub fn register<E>(&mut self, encoder: E)
+    where
+        E: Encoder + 'static,
+    {
+        self.encoders.retain(|e| e.name() != encoder.name());
+        self.encoders.push(Box::new(encoder));
+    }
+
+    /// Get an encoder by name.
+    pub fn get(&self, name: &str) -> Option<&dyn Encoder> {
+        self.encoders
+            .iter()
+            .find(|encoder| encoder.name() == name)
+            .map(AsRef::as_ref)
+    }
+
+    /// Iterate over the registered encoders, in order of registration.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Encoder> {
+        self.encoders.iter().map(AsRef::as_ref)
+    }
+
+    /// Get the names of the registered encoders.
+    pub fn names(&self) -> Vec<&str> {
+        self.encoders.iter().map(|encoder| encoder.name()).collect()
+    }
+}
+
+impl Default for EncoderRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(LinesEncoder);
+        registry.register(FrontCodingEncoder);
+        registry.register(FrontCodingOptEncoder);
+        registry.register(BlockedFrontCodingEncoder::default());
+        registry.register(TrieEncoder);
+        registry.register(DawgEncoder);
+        registry.register(HuffmanEncoder::default());
+        registry.register(BloomEncoder::default());
+        registry
+    }
+}
+
+impl std::fmt::Debug for EncoderRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("EncoderRegistry")
+            .field(&self.names())
+            .finish()
+    }
+}
+
+/// One word per line, without compression (for benchmarking).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LinesEncoder;
+
+impl Encoder for LinesEncoder {
+    fn name(&self) -> &str {
+        "lines"
+    }
+
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+        encode_lines(writer, words)
+    }
+
+    fn decode(&self, content: &[u8], _length: usize) -> Result<Vec<String>, DecodeError> {
+        Ok(decode_lines(utf8(content)?))
+    }
+}
+
+/// Front coding, one suffix per line.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrontCodingEncoder;
+
+impl Encoder for FrontCodingEncoder {
+    fn name(&self) -> &str {
+        "front"
+    }
+
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+        encode_front_coding(writer, words)
+    }
+
+    fn decode(&self, content: &[u8], length: usize) -> Result<Vec<String>, DecodeError> {
+        decode_front_coding(utf8(content)?, length)
+    }
+}
+
+/// Front coding, with suffix lengths instead of new lines.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrontCodingOptEncoder;
+
+impl Encoder for FrontCodingOptEncoder {
+    fn name(&self) -> &str {
+        "frontopt"
+    }
+
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+        check_same_length(words)?;
+        encode_front_coding_opt(writer, words)
+    }
+
+    fn decode(&self, content: &[u8], length: usize) -> Result<Vec<String>, DecodeError> {
+        decode_front_coding_opt(utf8(content)?, length)
+    }
+}
+
+/// Trie, in depth-first order.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrieEncoder;
+
+impl Encoder for TrieEncoder {
+    fn name(&self) -> &str {
+        "trie"
+    }
+
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+        encode_trie(writer, words)
+    }
+
+    fn decode(&self, content: &[u8], _length: usize) -> Result<Vec<String>, DecodeError> {
+        decode_trie(utf8(content)?)
+    }
+}
+
+/// Check that words have the same length, for the encoders that rely on it.
+fn check_same_length(words: &[&str]) -> Result<(), std::io::Error> {
+    match words.first() {
+        Some(first) if words.iter().any(|word| word.len() != first.len()) => {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "all words must have the same length",
+            ))
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Read an encoded text.
+fn utf8(content: &[u8]) -> Result<&str, DecodeError> {
+    std::str::from_utf8(content).map_err(|err| DecodeError::new(format!("not a text: {err}")))
+}
+
+/// Encode a list of words line by line, without compression (for benchmarking).
+pub fn encode_lines<W, I, T>(writer: &mut W, iter: I) -> Result<(), std::io::Error>
 where
-    P: AsRef<Path>,
+    W: Write + ?Sized,
     I: IntoIterator<Item = T>,
     T: AsRef<str>,
 {
-    let mut writer = BufWriter::new(File::create(path)?);
-
     for word in iter {
         let word = word.as_ref();
         writeln!(writer, "{word}")?;
@@ -22,26 +242,58 @@ where
     Ok(())
 }
 
-/// Write a list of words using a front coding algorithm.
-/// Assumes all words have the same length.
+/// Encode a list of words using a front coding algorithm.
 /// This variant encodes one word per line.
-pub fn write_front_coding<P, I, T>(path: P, iter: I) -> Result<(), std::io::Error>
+///
+/// Each line holds the suffix of a word after the prefix shared with the
+/// previous word. The prefix length is implied when the word has the maximum
+/// length; otherwise it is written before the suffix (e.g. `2c` for "abc"
+/// after "abd" when the maximum length is 4).
+pub fn encode_front_coding<W, I, T>(writer: &mut W, iter: I) -> Result<(), std::io::Error>
 where
-    P: AsRef<Path>,
+    W: Write + ?Sized,
     I: IntoIterator<Item = T>,
     T: AsRef<str>,
 {
-    let mut writer = BufWriter::new(File::create(path)?);
+    let words: Vec<T> = iter.into_iter().collect();
+    let length = words
+        .iter()
+        .map(|word| word.as_ref().len())
+        .max()
+        .unwrap_or(0);
 
+    encode_front_coding_with_length(writer, words, length)
+}
+
+/// Encode a list of words using a front coding algorithm, given the maximum
+/// length of the words.
+///
+/// See [`encode_front_coding`].
+pub fn encode_front_coding_with_length<W, I, T>(
+    writer: &mut W,
+    iter: I,
+    length: usize,
+) -> Result<(), std::io::Error>
+where
+    W: Write + ?Sized,
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+{
     let mut previous = String::new();
 
-    for current in iter {
-        let prefix_length = common_prefix_length(&previous, &current.as_ref());
+    for (index, current) in iter.into_iter().enumerate() {
+        let prefix_length = common_prefix_length(&previous, current.as_ref());
         let suffix = &current.as_ref()[prefix_length..];
 
-        if !previous.is_empty() {
-            write!(writer, "\n")?;
+        if index > 0 {
+            writeln!(writer)?;
+        }
+
+        // Shorter words need an explicit prefix length
+        if length.checked_sub(suffix.len()) != Some(prefix_length) {
+            write!(writer, "{prefix_length}")?;
         }
+
         write!(writer, "{suffix}")?;
 
         previous = current.as_ref().to_owned();
@@ -50,24 +302,22 @@ where
     Ok(())
 }
 
-/// Write a list of words using an optimized front coding algorithm.
+/// Encode a list of words using an optimized front coding algorithm.
 /// Assumes all words have the same length.
 /// This variant encodes the suffix length when it is greater than 1, instead of
 /// writing new lines.
-/// It should reduce the dictionary size compared to `write_front_coding_lines`,
+/// It should reduce the dictionary size compared to `encode_front_coding`,
 /// but decoding is more tedious.
-pub fn write_front_coding_opt<P, I, T>(path: P, iter: I) -> Result<(), std::io::Error>
+pub fn encode_front_coding_opt<W, I, T>(writer: &mut W, iter: I) -> Result<(), std::io::Error>
 where
-    P: AsRef<Path>,
+    W: Write + ?Sized,
     I: IntoIterator<Item = T>,
     T: AsRef<str>,
 {
-    let mut writer = BufWriter::new(File::create(path)?);
-
     let mut previous = String::new();
 
     for current in iter {
-        let prefix_length = common_prefix_length(&previous, &current.as_ref());
+        let prefix_length = common_prefix_length(&previous, current.as_ref());
         let suffix = &current.as_ref()[prefix_length..];
 
         if !previous.is_empty() && suffix.len() > 1 {
@@ -96,23 +346,20 @@ where
         .count()
 }
 
-/// Write a list of words using the trie algorithm.
-/// Assumes all words have the same length.
-pub fn write_trie<P, I, T>(path: P, iter: I) -> Result<(), std::io::Error>
+/// Encode a list of words using the trie algorithm.
+pub fn encode_trie<W, I, T>(writer: &mut W, iter: I) -> Result<(), std::io::Error>
 where
-    P: AsRef<Path>,
+    W: Write + ?Sized,
     I: IntoIterator<Item = T>,
     T: AsRef<str>,
 {
-    let mut writer = BufWriter::new(File::create(path)?);
-
     let mut trie = Trie::default();
 
     for current in iter {
         trie.insert(current);
     }
 
-    trie.write(&mut writer)?;
+    trie.write(writer)?;
 
     Ok(())
 }
@@ -120,6 +367,8 @@ where
 /// Trie node.
 #[derive(Debug, Default)]
 struct Trie {
+    /// Whether a word ends at this node.
+    terminal: bool,
     /// Children nodes.
     children: BTreeMap<char, Trie>,
 }
@@ -134,25 +383,366 @@ impl Trie {
         for c in word.as_ref().chars() {
             node = node.children.entry(c).or_default();
         }
+        node.terminal = true;
     }
 
     /// Write the trie by encoding each node as
-    /// `{character}{num_children}{...descendants}`.
+    /// `{character}[$]{num_children}{...descendants}`, where `$` marks the end
+    /// of a word that is the prefix of other words.
+    /// A `$` at the start marks the empty word.
     pub fn write<W>(&self, writer: &mut W) -> Result<(), std::io::Error>
     where
-        W: std::io::Write,
+        W: Write + ?Sized,
+    {
+        if self.terminal {
+            write!(writer, "$")?;
+        }
+        self.write_children(writer)
+    }
+
+    fn write_children<W>(&self, writer: &mut W) -> Result<(), std::io::Error>
+    where
+        W: Write + ?Sized,
     {
         for (c, node) in self.children.iter() {
             let n = node.children.len();
-            let buffer = if n > 0 {
-                format!("{c}{n}")
-            } else {
-                // Since the word length is known, we can omit the '0'
-                format!("{c}")
+            let buffer = match (n, node.terminal) {
+                (0, _) => {
+                    // Leaves are words, so we can omit the '0'
+                    format!("{c}")
+                },
+                (_, true) => format!("{c}${n}"),
+                (_, false) => format!("{c}{n}"),
             };
-            writer.write(buffer.as_bytes())?;
-            node.write(writer)?;
+            writer.write_all(buffer.as_bytes())?;
+            node.write_children(writer)?;
         }
         Ok(())
     }
 }
+
+/// Decode a list of words written line by line.
+pub fn decode_lines(content: &str) -> Vec<String> {
+    content.lines().map(str::to_owned).collect()
+}
+
+/// Decode a list of words of a given maximum length, written with
+/// `encode_front_coding`.
+pub fn decode_front_coding(content: &str, length: usize) -> Result<Vec<String>, DecodeError> {
+    let mut words = Vec::new();
+    let mut previous = String::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let suffix = line.trim_start_matches(|c: char| c.is_ascii_digit());
+        let digits = &line[..line.len() - suffix.len()];
+
+        let prefix_length = if digits.is_empty() {
+            length.checked_sub(suffix.len())
+        } else {
+            digits.parse().ok()
+        }
+        .ok_or_else(|| {
+            DecodeError(format!(
+                "suffix {suffix:?} at line {line_number} is too long"
+            ))
+        })?;
+        let prefix = previous.get(..prefix_length).ok_or_else(|| {
+            DecodeError(format!(
+                "suffix {suffix:?} at line {line_number} is too short"
+            ))
+        })?;
+
+        let word = format!("{prefix}{suffix}");
+        if word.len() > length {
+            return Err(DecodeError(format!(
+                "suffix {suffix:?} at line {line_number} is too long"
+            )));
+        }
+
+        words.push(word.to_owned());
+        previous = word;
+    }
+
+    Ok(words)
+}
+
+/// Decode a list of words of a given length, written with
+/// `encode_front_coding_opt`.
+pub fn decode_front_coding_opt(content: &str, length: usize) -> Result<Vec<String>, DecodeError> {
+    let mut words = Vec::new();
+    let mut previous = String::new();
+    let mut rest = content.trim_end_matches(['\r', '\n']);
+
+    while !rest.is_empty() {
+        // The first word is written in full, next ones have a suffix length
+        let suffix_length = if words.is_empty() {
+            length
+        } else {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits == 0 {
+                1
+            } else {
+                let offset: usize = rest[..digits]
+                    .parse()
+                    .map_err(|err| DecodeError(format!("invalid suffix length: {err}")))?;
+                rest = &rest[digits..];
+                offset + 1
+            }
+        };
+
+        let suffix = rest.get(..suffix_length).ok_or_else(|| {
+            DecodeError(format!("truncated suffix {rest:?} after word {previous:?}"))
+        })?;
+        let prefix_length = length.checked_sub(suffix_length).ok_or_else(|| {
+            DecodeError(format!(
+                "suffix {suffix:?} after word {previous:?} is too long"
+            ))
+        })?;
+        rest = &rest[suffix_length..];
+
+        let word = format!("{}{suffix}", &previous[..prefix_length.min(previous.len())]);
+        if word.len() != length {
+            return Err(DecodeError(format!("invalid word {word:?}")));
+        }
+        words.push(word.to_owned());
+        previous = word;
+    }
+
+    Ok(words)
+}
+
+/// Decode a list of words written with `encode_trie`.
+pub fn decode_trie(content: &str) -> Result<Vec<String>, DecodeError> {
+    let mut words = Vec::new();
+    let mut chars = content.trim_end_matches(['\r', '\n']).chars().peekable();
+    let mut prefix = String::new();
+
+    // The empty word
+    if chars.next_if_eq(&'$').is_some() {
+        words.push(String::new());
+    }
+
+    // Number of remaining children of each node of the current branch
+    let mut remaining: Vec<usize> = Vec::new();
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '$' {
+            return Err(DecodeError(format!("unexpected {c:?} after {prefix:?}")));
+        }
+
+        // Word that is the prefix of other words
+        let terminal = chars.next_if_eq(&'$').is_some();
+
+        let mut n: usize = 0;
+        while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+            n = n
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(digit as usize))
+                .ok_or_else(|| DecodeError(format!("number too large after {prefix:?}")))?;
+            chars.next();
+        }
+
+        if let Some(count) = remaining.last_mut() {
+            *count -= 1;
+        }
+
+        prefix.push(c);
+
+        if n > 0 {
+            if terminal {
+                words.push(prefix.to_owned());
+            }
+            remaining.push(n);
+            continue;
+        }
+
+        // Leaf: the branch is a word
+        words.push(prefix.to_owned());
+        prefix.pop();
+
+        // Go up while the nodes have no remaining child
+        while remaining.last() == Some(&0) {
+            remaining.pop();
+            prefix.pop();
+        }
+    }
+
+    if !remaining.is_empty() {
+        return Err(DecodeError(format!("missing children after {prefix:?}")));
+    }
+
+    Ok(words)
+}
+
+/// Error while decoding a dictionary.
+#[derive(Debug)]
+pub struct DecodeError(String);
+
+impl DecodeError {
+    pub fn new<S>(message: S) -> Self
+    where
+        S: ToString,
+    {
+        Self(message.to_string())
+    }
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid encoded dictionary: {}", self.0)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sub-dictionary of French words of length 7 starting with "v", without
+    /// their first letter.
+    const DICTIONARY_7V: &str = "accine acarme aguent aincre alable alence alider alises allees \
+        alseur ampire anille anites apeurs arices ariete assale autour eillee eineux elours \
+        endeur engeur erdure erglas ernier errier ersant ertige estige eteran euvage ibrant \
+        icaire ictime idange ietnam illage ilaine iolent irgule irtuel isiter itrail itrine \
+        ivante ocable oilage oisine oiture olable olcans olonte ouloir oyager oyelle oyeurs";
+
+    #[test]
+    fn round_trip() {
+        let length = 6;
+        let mut words: Vec<&str> = DICTIONARY_7V.split_whitespace().collect();
+        words.sort();
+
+        let registry = EncoderRegistry::default();
+
+        for name in registry.names() {
+            let encoder = registry.get(name).unwrap();
+            let trained = encoder
+                .train(&[words.clone()])
+                .map(|(trained, table)| (trained, encoder.load(&table).unwrap()));
+            let (encoder, decoder): (&dyn Encoder, &dyn Encoder) = match &trained {
+                Some((trained, loaded)) => (trained.as_ref(), loaded.as_ref()),
+                None => (encoder, encoder),
+            };
+            let mut content = Vec::new();
+            encoder.encode(&mut content, &words).unwrap();
+            for word in words.iter() {
+                assert!(decoder.contains(&content, length, word).unwrap(), "{name}");
+            }
+            // Approximate encoders (Bloom filters) cannot list their words
+            if encoder.is_exact() {
+                assert_eq!(decoder.decode(&content, length).unwrap(), words, "{name}");
+                assert!(!decoder.contains(&content, length, "zzzzzz").unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn register_encoder() {
+        struct Reversed;
+
+        impl Encoder for Reversed {
+            fn name(&self) -> &str {
+                "lines"
+            }
+
+            fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+                encode_lines(writer, words.iter().rev())
+            }
+
+            fn decode(&self, content: &[u8], _length: usize) -> Result<Vec<String>, DecodeError> {
+                Ok(decode_lines(utf8(content)?).into_iter().rev().collect())
+            }
+        }
+
+        let mut registry = EncoderRegistry::default();
+        registry.register(Reversed);
+
+        assert_eq!(
+            registry.names(),
+            [
+                "front",
+                "frontopt",
+                "frontblock",
+                "trie",
+                "dawg",
+                "huffman",
+                "bloom",
+                "lines"
+            ]
+        );
+
+        let mut content = Vec::new();
+        let encoder = registry.get("lines").unwrap();
+        encoder.encode(&mut content, &["bc", "de"]).unwrap();
+        assert_eq!(content, b"de\nbc\n");
+        assert!(registry.get("foo").is_none());
+
+        let approximate: Vec<_> = registry
+            .iter()
+            .filter(|encoder| !encoder.is_exact())
+            .map(|encoder| encoder.name())
+            .collect();
+        assert_eq!(approximate, ["bloom"]);
+    }
+
+    #[test]
+    fn decode_examples() {
+        assert_eq!(
+            decode_front_coding("abc\nd\nef", 3).unwrap(),
+            ["abc", "abd", "aef"]
+        );
+        assert_eq!(
+            decode_front_coding_opt("abcd1ef", 3).unwrap(),
+            ["abc", "abd", "aef"]
+        );
+        assert_eq!(decode_trie("a2b2cde1f").unwrap(), ["abc", "abd", "aef"]);
+    }
+
+    #[test]
+    fn mixed_lengths() {
+        let words = ["", "ab", "abc", "abd", "b", "bcd"];
+        let length = 3;
+
+        let mut content = Vec::new();
+        encode_front_coding(&mut content, words).unwrap();
+        assert_eq!(content, b"0\n0ab\nc\nd\n0b\ncd");
+
+        let mut content = Vec::new();
+        encode_trie(&mut content, words).unwrap();
+        assert_eq!(content, b"$a1b$2cdb$1c1d");
+
+        let registry = EncoderRegistry::default();
+
+        for name in registry.names() {
+            let encoder = registry.get(name).unwrap();
+            let mut content = Vec::new();
+            let result = encoder.encode(&mut content, &words);
+
+            if matches!(name, "frontopt" | "huffman") {
+                assert!(result.is_err(), "{name}");
+                continue;
+            }
+
+            result.unwrap();
+            for word in words {
+                assert!(encoder.contains(&content, length, word).unwrap(), "{name}");
+            }
+            if name != "bloom" {
+                assert_eq!(encoder.decode(&content, length).unwrap(), words, "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert!(decode_front_coding("abc\nabcd", 3).is_err());
+        assert!(decode_front_coding("abc\n4d", 3).is_err());
+        assert!(decode_front_coding("abc\n2de", 3).is_err());
+        assert!(decode_front_coding_opt("abc5d", 3).is_err());
+        assert!(decode_trie("a3bc").is_err());
+        assert!(decode_trie("a12").is_err());
+        assert!(decode_trie("a99999999999999999999999b").is_err());
+    }
+}
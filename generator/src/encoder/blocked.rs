@@ -0,0 +1,197 @@
+use std::io::Write;
+
+use super::{decode_front_coding, encode_front_coding_with_length, utf8, DecodeError, Encoder};
+
+/// Front coding in blocks, for random access.
+///
+/// Words are split into blocks of `block_size` words, and each block is front
+/// coded on its own, so that its first word (the restart point) is written in
+/// full. The first line is the restart index: the block size, followed by the
+/// byte offsets of the restart points after the first one, from the start of
+/// the second line. The next lines are the blocks, one suffix per line like
+/// `front`.
+///
+/// A word is looked up by binary search on the restart points, then by
+/// decoding a single block.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockedFrontCodingEncoder {
+    block_size: usize,
+}
+
+impl BlockedFrontCodingEncoder {
+    /// Create a blocked front coding encoder, given the number of words per
+    /// block (at least 1).
+    pub fn new(block_size: usize) -> Self {
+        Self { block_size }
+    }
+}
+
+impl Default for BlockedFrontCodingEncoder {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl Encoder for BlockedFrontCodingEncoder {
+    fn name(&self) -> &str {
+        "frontblock"
+    }
+
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+        if self.block_size == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "the block size must be at least 1",
+            ));
+        }
+
+        if words.is_empty() {
+            return Ok(());
+        }
+
+        let length = words.iter().map(|word| word.len()).max().unwrap_or(0);
+
+        let mut blocks = Vec::new();
+        let mut offsets = Vec::new();
+
+        for (index, block) in words.chunks(self.block_size).enumerate() {
+            if index > 0 {
+                writeln!(blocks)?;
+                offsets.push(blocks.len());
+            }
+            encode_front_coding_with_length(&mut blocks, block, length)?;
+        }
+
+        write!(writer, "{}", self.block_size)?;
+        for offset in offsets {
+            write!(writer, " {offset}")?;
+        }
+        writeln!(writer)?;
+
+        writer.write_all(&blocks)
+    }
+
+    fn decode(&self, content: &[u8], length: usize) -> Result<Vec<String>, DecodeError> {
+        let index = RestartIndex::new(utf8(content)?)?;
+        decode_front_coding(index.blocks, length)
+    }
+
+    fn contains(&self, content: &[u8], length: usize, word: &str) -> Result<bool, DecodeError> {
+        let index = RestartIndex::new(utf8(content)?)?;
+        index.contains(length, word)
+    }
+}
+
+/// Restart index of blocked front coding.
+struct RestartIndex<'a> {
+    /// Byte offsets of the restart points in `blocks`, from 0.
+    offsets: Vec<usize>,
+    /// Front coded blocks.
+    blocks: &'a str,
+}
+
+impl<'a> RestartIndex<'a> {
+    /// Parse the restart index.
+    fn new(content: &'a str) -> Result<Self, DecodeError> {
+        if content.is_empty() {
+            return Ok(Self {
+                offsets: Vec::new(),
+                blocks: "",
+            });
+        }
+
+        let (header, blocks) = content
+            .split_once('\n')
+            .ok_or_else(|| DecodeError::new("missing restart index"))?;
+
+        let mut numbers = header.split(' ').map(|number| {
+            number
+                .parse::<usize>()
+                .map_err(|_| DecodeError::new(format!("invalid restart index {header:?}")))
+        });
+
+        // The block size is only needed to encode
+        numbers.next().transpose()?;
+
+        let offsets: Vec<usize> = std::iter::once(Ok(0))
+            .chain(numbers)
+            .collect::<Result<_, _>>()?;
+
+        // Each restart point follows a new line
+        for pair in offsets.windows(2) {
+            if pair[0] >= pair[1]
+                || pair[1] > blocks.len()
+                || blocks.as_bytes()[pair[1] - 1] != b'\n'
+            {
+                return Err(DecodeError::new(format!(
+                    "invalid restart point {}",
+                    pair[1]
+                )));
+            }
+        }
+
+        Ok(Self { offsets, blocks })
+    }
+
+    /// Check whether a word is in the blocks.
+    fn contains(&self, length: usize, word: &str) -> Result<bool, DecodeError> {
+        // Number of restart points whose word is not greater than the word
+        let count = self
+            .offsets
+            .partition_point(|&offset| self.restart_word(offset) <= word);
+
+        let Some(block) = count.checked_sub(1) else {
+            return Ok(false);
+        };
+
+        let start = self.offsets[block];
+        let end = self
+            .offsets
+            .get(block + 1)
+            .map_or(self.blocks.len(), |offset| offset - 1);
+
+        let words = decode_front_coding(&self.blocks[start..end], length)?;
+
+        Ok(words.binary_search_by(|w| w.as_str().cmp(word)).is_ok())
+    }
+
+    /// Word written in full at a restart point.
+    fn restart_word(&self, offset: usize) -> &str {
+        let line = &self.blocks[offset..];
+        let line = line.split_once('\n').map_or(line, |(word, _)| word);
+        // Shorter words start with a prefix length of 0
+        line.trim_start_matches(|c: char| c.is_ascii_digit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_points() {
+        let words = ["abc", "abd", "acd", "bcd", "bce"];
+        let encoder = BlockedFrontCodingEncoder::new(2);
+
+        let mut content = Vec::new();
+        encoder.encode(&mut content, &words).unwrap();
+        assert_eq!(content, b"2 6 14\nabc\nd\nacd\nbcd\nbce");
+
+        assert_eq!(encoder.decode(&content, 3).unwrap(), words);
+
+        for word in words {
+            assert!(encoder.contains(&content, 3, word).unwrap(), "{word}");
+        }
+        for word in ["aaa", "abe", "ace", "bcc", "bcf", "zzz"] {
+            assert!(!encoder.contains(&content, 3, word).unwrap(), "{word}");
+        }
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let encoder = BlockedFrontCodingEncoder::default();
+        assert!(encoder.decode(b"abc", 3).is_err());
+        assert!(encoder.decode(b"2 5\nabc\nd\nacd", 3).is_err());
+        assert!(encoder.contains(b"2 20\nabc\nd\nacd", 3, "abc").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use std::io::Write;
+
+use super::{DecodeError, Encoder};
+
+/// Bloom filter, which only answers whether a word is accepted.
+///
+/// The words cannot be listed back, and words that are not in the
+/// sub-dictionary are accepted with a probability close to the false positive
+/// rate.
+///
+/// A filter is written as the number of hash functions `k` (one byte),
+/// followed by the bits of the filter (least significant bit first). The `i`-th
+/// hash of a word is `mix(h + i * 0x9e3779b97f4a7c15) mod m`, where `h` is the
+/// 64-bit FNV-1a hash of the word, `mix` is the finalizer of SplitMix64, and
+/// `m` is the number of bits.
+#[derive(Clone, Copy, Debug)]
+pub struct BloomEncoder {
+    false_positive_rate: f64,
+}
+
+impl BloomEncoder {
+    /// Create a Bloom filter encoder, given its false positive rate (between 0
+    /// and 1, exclusive).
+    pub fn new(false_positive_rate: f64) -> Self {
+        Self {
+            false_positive_rate,
+        }
+    }
+}
+
+impl Default for BloomEncoder {
+    fn default() -> Self {
+        Self::new(0.01)
+    }
+}
+
+impl Encoder for BloomEncoder {
+    fn name(&self) -> &str {
+        "bloom"
+    }
+
+    fn extension(&self) -> &str {
+        "bloom"
+    }
+
+    fn is_exact(&self) -> bool {
+        false
+    }
+
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+        if !(self.false_positive_rate > 0.0 && self.false_positive_rate < 1.0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "the false positive rate must be between 0 and 1, got {}",
+                    self.false_positive_rate
+                ),
+            ));
+        }
+
+        let filter = BloomFilter::new(words, self.false_positive_rate);
+        writer.write_all(&[filter.num_hashes])?;
+        writer.write_all(&filter.bits)
+    }
+
+    fn decode(&self, _content: &[u8], _length: usize) -> Result<Vec<String>, DecodeError> {
+        Err(DecodeError::new("a Bloom filter cannot list its words"))
+    }
+
+    fn contains(&self, content: &[u8], _length: usize, word: &str) -> Result<bool, DecodeError> {
+        let filter = BloomFilter::from_bytes(content)?;
+        Ok(filter.contains(word))
+    }
+}
+
+/// Bloom filter.
+#[derive(Clone, Debug, PartialEq)]
+struct BloomFilter {
+    /// Number of hash functions.
+    num_hashes: u8,
+    bits: Vec<u8>,
+}
+
+impl BloomFilter {
+    /// Create the smallest filter of a list of words with the given false
+    /// positive rate.
+    fn new(words: &[&str], false_positive_rate: f64) -> Self {
+        let n = words.len() as f64;
+        let ln2 = std::f64::consts::LN_2;
+
+        // Optimal number of bits, rounded up to whole bytes
+        let num_bits = (-n * false_positive_rate.ln() / (ln2 * ln2)).ceil() as usize;
+        let num_bytes = num_bits.div_ceil(8);
+
+        // Optimal number of hash functions for the actual number of bits
+        let num_hashes = if words.is_empty() {
+            1
+        } else {
+            ((num_bytes * 8) as f64 / n * ln2).round().clamp(1.0, 255.0) as u8
+        };
+
+        let mut filter = Self {
+            num_hashes,
+            bits: vec![0; num_bytes],
+        };
+
+        for word in words {
+            for index in filter.indices(word) {
+                filter.bits[index / 8] |= 1 << (index % 8);
+            }
+        }
+
+        filter
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        match bytes.split_first() {
+            Some((&num_hashes, bits)) if num_hashes > 0 => Ok(Self {
+                num_hashes,
+                bits: bits.to_vec(),
+            }),
+            Some(_) => Err(DecodeError::new("invalid number of hash functions")),
+            None => Err(DecodeError::new("missing number of hash functions")),
+        }
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        // An empty filter has no bits, so it contains no words
+        !self.bits.is_empty()
+            && self
+                .indices(word)
+                .all(|index| self.bits[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// Indices of the bits of a word.
+    fn indices(&self, word: &str) -> impl Iterator<Item = usize> {
+        let num_bits = (self.bits.len() * 8) as u64;
+        let hash = fnv1a(word.as_bytes());
+
+        // Double hashing (h1 + i * h2) would only give a few distinct sets of
+        // bits in the small filters of rare lengths and letters.
+        (0..self.num_hashes as u64).map(move |i| {
+            (mix(hash.wrapping_add(i.wrapping_mul(0x9e37_79b9_7f4a_7c15))) % num_bits) as usize
+        })
+    }
+}
+
+/// Mix the bits of a hash (finalizer of SplitMix64).
+fn mix(mut hash: u64) -> u64 {
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// 64-bit FNV-1a hash.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn membership() {
+        let words = ["aient", "aimes", "oient", "oimes"];
+        let mut content = Vec::new();
+        BloomEncoder::new(0.001)
+            .encode(&mut content, &words)
+            .unwrap();
+
+        // 4 words need 58 bits at 0.1%
+        assert_eq!(content.len(), 1 + 8);
+
+        for word in words {
+            assert!(BloomEncoder::default().contains(&content, 5, word).unwrap());
+        }
+        assert!(BloomEncoder::default().decode(&content, 5).is_err());
+
+        // An empty filter contains no words
+        let mut content = Vec::new();
+        BloomEncoder::default().encode(&mut content, &[]).unwrap();
+        assert!(!BloomEncoder::default()
+            .contains(&content, 5, "aient")
+            .unwrap());
+    }
+
+    #[test]
+    fn fnv1a_hash() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -0,0 +1,401 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+};
+
+use super::{utf8, DecodeError, Encoder};
+
+/// Minimal deterministic acyclic word automaton (DAWG).
+///
+/// Unlike the trie, the automaton also shares the suffixes of the words (e.g.
+/// "-ement", "-aient"). It is written in depth-first order like the trie, but
+/// a node reached by several transitions is written once, and referenced
+/// afterwards.
+///
+/// After its incoming character, a node is written as
+/// `[$][=][{num_children}]{...transitions}` or `:{id}`, where:
+///
+/// - `$` marks the end of a word before the last character (only for words
+///   shorter than the others),
+/// - `=` marks a shared node, which takes the next id (from 0),
+/// - `{num_children}` is omitted when it is 1,
+/// - `:{id}` references a shared node already written.
+///
+/// Nodes at the word length have no children and are not written.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DawgEncoder;
+
+impl Encoder for DawgEncoder {
+    fn name(&self) -> &str {
+        "dawg"
+    }
+
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+        let length = words.iter().map(|word| word.len()).max().unwrap_or(0);
+        let dawg = Dawg::new(words);
+
+        if dawg.nodes.is_empty() {
+            return Ok(());
+        }
+
+        // Number of transitions leading to each node
+        let mut in_degree = vec![0; dawg.nodes.len()];
+        for node in dawg.nodes.iter() {
+            for (_, child) in node.children.iter() {
+                if let Some(child) = child {
+                    in_degree[*child] += 1;
+                }
+            }
+        }
+
+        let mut writer = DawgWriter {
+            dawg: &dawg,
+            in_degree,
+            ids: HashMap::new(),
+            length,
+            writer,
+        };
+
+        writer.write(Some(0), 0)
+    }
+
+    fn decode(&self, content: &[u8], length: usize) -> Result<Vec<String>, DecodeError> {
+        let content = utf8(content)?.trim_end_matches(['\r', '\n']);
+
+        if content.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut reader = DawgReader {
+            chars: content.chars().peekable(),
+            dawg: Dawg::default(),
+            shared: Vec::new(),
+            length,
+        };
+
+        reader.read(0)?;
+
+        if let Some(c) = reader.chars.next() {
+            return Err(DecodeError::new(format!(
+                "unexpected character '{c}' at the end"
+            )));
+        }
+
+        reader.dawg.words(length)
+    }
+}
+
+/// Node of the automaton.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Node {
+    /// Whether a word ends at this node.
+    terminal: bool,
+    /// Transitions, to the index of a node, or to `None` for the final node
+    /// (the end of a word, without children).
+    children: Vec<(char, Option<usize>)>,
+}
+
+/// Automaton, whose first node is the root.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Dawg {
+    nodes: Vec<Node>,
+}
+
+impl Dawg {
+    /// Build the minimal automaton of a list of words.
+    fn new(words: &[&str]) -> Self {
+        if words.is_empty() {
+            return Self::default();
+        }
+
+        // Build the trie, in an arena whose first node is the root
+        let mut trie: Vec<(bool, BTreeMap<char, usize>)> = vec![(false, BTreeMap::new())];
+
+        for word in words {
+            let mut node = 0;
+            for c in word.chars() {
+                node = match trie[node].1.get(&c) {
+                    Some(&child) => child,
+                    None => {
+                        trie.push((false, BTreeMap::new()));
+                        let child = trie.len() - 1;
+                        trie[node].1.insert(c, child);
+                        child
+                    },
+                };
+            }
+            trie[node].0 = true;
+        }
+
+        // Merge equivalent nodes, from the leaves to the root.
+        // Children are created after their parent, hence the reverse order.
+        let mut classes: HashMap<(bool, Vec<(char, usize)>), usize> = HashMap::new();
+        let mut class_of = vec![0; trie.len()];
+        let mut representatives: Vec<usize> = Vec::new();
+
+        for node in (0..trie.len()).rev() {
+            let (terminal, children) = &trie[node];
+            let signature = (
+                *terminal,
+                children
+                    .iter()
+                    .map(|(&c, &child)| (c, class_of[child]))
+                    .collect(),
+            );
+            class_of[node] = *classes.entry(signature).or_insert_with(|| {
+                representatives.push(node);
+                representatives.len() - 1
+            });
+        }
+
+        // The final node is the terminal node without children
+        let final_class = classes.get(&(true, Vec::new())).copied();
+
+        // Number the other classes in depth-first order from the root
+        let mut index_of: HashMap<usize, usize> = HashMap::new();
+        let mut order: Vec<usize> = Vec::new();
+        let mut stack = vec![class_of[0]];
+
+        while let Some(class) = stack.pop() {
+            if index_of.contains_key(&class) || (Some(class) == final_class && class != class_of[0])
+            {
+                continue;
+            }
+            index_of.insert(class, order.len());
+            order.push(class);
+            let children = &trie[representatives[class]].1;
+            stack.extend(children.values().rev().map(|&child| class_of[child]));
+        }
+
+        let nodes = order
+            .into_iter()
+            .map(|class| {
+                let (terminal, children) = &trie[representatives[class]];
+                Node {
+                    terminal: *terminal,
+                    children: children
+                        .iter()
+                        .map(|(&c, &child)| (c, index_of.get(&class_of[child]).copied()))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self { nodes }
+    }
+
+    /// List the words of the automaton, in lexicographic order.
+    /// Words are at most `length` characters long.
+    fn words(&self, length: usize) -> Result<Vec<String>, DecodeError> {
+        let mut words = Vec::new();
+
+        if self.nodes.is_empty() {
+            return Ok(words);
+        }
+
+        // Nodes and positions of the next transition along the current branch
+        let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+        let mut prefix = String::new();
+
+        if self.nodes[0].terminal {
+            words.push(String::new());
+        }
+
+        while let Some((node, position)) = stack.last_mut() {
+            let Some(&(c, target)) = self.nodes[*node].children.get(*position) else {
+                stack.pop();
+                prefix.pop();
+                continue;
+            };
+            *position += 1;
+
+            prefix.push(c);
+
+            if prefix.len() > length {
+                return Err(DecodeError::new(format!(
+                    "word {prefix:?} is longer than {length} characters"
+                )));
+            }
+
+            match target {
+                None => {
+                    words.push(prefix.to_owned());
+                    prefix.pop();
+                },
+                Some(target) => {
+                    if self.nodes[target].terminal {
+                        words.push(prefix.to_owned());
+                    }
+                    stack.push((target, 0));
+                },
+            }
+        }
+
+        Ok(words)
+    }
+}
+
+/// Writer of an automaton.
+struct DawgWriter<'a, 'w> {
+    dawg: &'a Dawg,
+    /// Number of transitions leading to each node.
+    in_degree: Vec<usize>,
+    /// Ids of the shared nodes already written.
+    ids: HashMap<usize, usize>,
+    /// Length of the longest words.
+    length: usize,
+    writer: &'w mut dyn Write,
+}
+
+impl DawgWriter<'_, '_> {
+    /// Write a node reached at a given depth.
+    fn write(&mut self, node: Option<usize>, depth: usize) -> Result<(), std::io::Error> {
+        let Some(node) = node else {
+            // Final node before the word length
+            if depth < self.length {
+                write!(self.writer, "$0")?;
+            }
+            return Ok(());
+        };
+
+        if let Some(id) = self.ids.get(&node) {
+            return write!(self.writer, ":{id}");
+        }
+
+        let dawg = self.dawg;
+        let Node { terminal, children } = &dawg.nodes[node];
+
+        if *terminal {
+            write!(self.writer, "$")?;
+        }
+
+        if self.in_degree[node] > 1 {
+            self.ids.insert(node, self.ids.len());
+            write!(self.writer, "=")?;
+        }
+
+        if children.len() != 1 {
+            write!(self.writer, "{}", children.len())?;
+        }
+
+        for (c, child) in children.iter() {
+            write!(self.writer, "{c}")?;
+            self.write(*child, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reader of an automaton.
+struct DawgReader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    dawg: Dawg,
+    /// Shared nodes, by id.
+    shared: Vec<usize>,
+    /// Length of the longest words.
+    length: usize,
+}
+
+impl DawgReader<'_> {
+    /// Read a node reached at a given depth, and return its index (or `None`
+    /// for the final node).
+    fn read(&mut self, depth: usize) -> Result<Option<usize>, DecodeError> {
+        if depth > 0 && depth == self.length {
+            return Ok(None);
+        }
+
+        if self.chars.next_if_eq(&':').is_some() {
+            let id = self.read_number()?.unwrap_or(usize::MAX);
+            let node = self
+                .shared
+                .get(id)
+                .ok_or_else(|| DecodeError::new(format!("unknown shared node at depth {depth}")))?;
+            return Ok(Some(*node));
+        }
+
+        let terminal = self.chars.next_if_eq(&'$').is_some();
+        let shared = self.chars.next_if_eq(&'=').is_some();
+        let num_children = self.read_number()?.unwrap_or(1);
+
+        if depth > 0 && terminal && !shared && num_children == 0 {
+            return Ok(None);
+        }
+
+        let node = self.dawg.nodes.len();
+        self.dawg.nodes.push(Node {
+            terminal,
+            children: Vec::new(),
+        });
+
+        if shared {
+            self.shared.push(node);
+        }
+
+        for _ in 0..num_children {
+            let c = self
+                .chars
+                .next()
+                .filter(|c| c.is_alphabetic())
+                .ok_or_else(|| DecodeError::new(format!("missing character at depth {depth}")))?;
+            let child = self.read(depth + 1)?;
+            self.dawg.nodes[node].children.push((c, child));
+        }
+
+        Ok(Some(node))
+    }
+
+    /// Read a decimal number, if any.
+    fn read_number(&mut self) -> Result<Option<usize>, DecodeError> {
+        let mut number: Option<usize> = None;
+
+        while let Some(digit) = self.chars.peek().and_then(|c| c.to_digit(10)) {
+            self.chars.next();
+            number = number
+                .unwrap_or(0)
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(digit as usize))
+                .map(Some)
+                .ok_or_else(|| DecodeError::new("number too large"))?;
+        }
+
+        Ok(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(words: &[&str]) -> String {
+        let mut content = Vec::new();
+        DawgEncoder.encode(&mut content, words).unwrap();
+        String::from_utf8(content).unwrap()
+    }
+
+    #[test]
+    fn share_suffixes() {
+        let words = ["aient", "aimes", "oient", "oimes"];
+        let content = encode(&words);
+
+        // "a" and "o" lead to the same node
+        assert_eq!(content, "2a=i2entmeso:0");
+        assert_eq!(DawgEncoder.decode(content.as_bytes(), 5).unwrap(), words);
+    }
+
+    #[test]
+    fn variable_lengths() {
+        let words = ["", "ab", "abc", "b"];
+        let content = encode(&words);
+
+        assert_eq!(content, "$2ab$cb$0");
+        assert_eq!(DawgEncoder.decode(content.as_bytes(), 3).unwrap(), words);
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert!(DawgEncoder.decode(b"2a=bcb:1", 2).is_err());
+        assert!(DawgEncoder.decode(b"2ab", 2).is_err());
+        assert!(DawgEncoder.decode(b"abc", 2).is_err());
+    }
+}
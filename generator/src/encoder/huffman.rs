@@ -0,0 +1,481 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    io::Write,
+};
+
+use super::{check_same_length, common_prefix_length, DecodeError, Encoder};
+
+/// Binary front coding, whose prefix lengths and suffix letters are coded with
+/// canonical Huffman codes.
+///
+/// The codes are trained on all the sub-dictionaries of a build, and written
+/// once in a table file. Each sub-dictionary is then
+/// written as the number of words (LEB128), followed by a bit stream (most
+/// significant bit first, padded with zeros) where each word is coded as its
+/// prefix length and its suffix letters. Since all words have the same length,
+/// the suffix length is implied.
+#[derive(Clone, Debug, Default)]
+pub struct HuffmanEncoder {
+    /// Trained codes.
+    table: Option<CodeTable>,
+}
+
+impl Encoder for HuffmanEncoder {
+    fn name(&self) -> &str {
+        "huffman"
+    }
+
+    fn extension(&self) -> &str {
+        "bin"
+    }
+
+    fn train(&self, dictionaries: &[Vec<&str>]) -> Option<(Box<dyn Encoder>, Vec<u8>)> {
+        let mut prefix_frequencies: BTreeMap<u8, usize> = BTreeMap::new();
+        let mut letter_frequencies: BTreeMap<u8, usize> = BTreeMap::new();
+
+        for words in dictionaries {
+            for (prefix_length, suffix) in front_coding(words) {
+                // Longer prefixes are rejected by `encode`
+                if let Ok(prefix_length) = u8::try_from(prefix_length) {
+                    *prefix_frequencies.entry(prefix_length).or_default() += 1;
+                }
+                for letter in suffix.bytes() {
+                    *letter_frequencies.entry(letter).or_default() += 1;
+                }
+            }
+        }
+
+        let table = CodeTable {
+            prefix: Code::new(&huffman_lengths(&prefix_frequencies)),
+            letter: Code::new(&huffman_lengths(&letter_frequencies)),
+        };
+        // A table that cannot be written is rejected by `encode`
+        let data = table.to_bytes().unwrap_or_default();
+
+        Some((Box::new(Self { table: Some(table) }), data))
+    }
+
+    fn load(&self, data: &[u8]) -> Result<Box<dyn Encoder>, DecodeError> {
+        let table = CodeTable::from_bytes(data)?;
+        Ok(Box::new(Self { table: Some(table) }))
+    }
+
+    fn encode(&self, writer: &mut dyn Write, words: &[&str]) -> Result<(), std::io::Error> {
+        let table = self.table.as_ref().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "the huffman encoder must be trained",
+            )
+        })?;
+        table.to_bytes()?;
+
+        check_same_length(words)?;
+
+        let mut buffer = Vec::new();
+        write_leb128(&mut buffer, words.len());
+
+        let mut bits = BitWriter::new(buffer);
+
+        for (prefix_length, suffix) in front_coding(words) {
+            let prefix_length = u8::try_from(prefix_length).map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("prefix length {prefix_length} is too long"),
+                )
+            })?;
+            table.prefix.write(&mut bits, prefix_length)?;
+            for letter in suffix.bytes() {
+                table.letter.write(&mut bits, letter)?;
+            }
+        }
+
+        writer.write_all(&bits.finish())
+    }
+
+    fn decode(&self, content: &[u8], length: usize) -> Result<Vec<String>, DecodeError> {
+        let table = self
+            .table
+            .as_ref()
+            .ok_or_else(|| DecodeError::new("missing code table"))?;
+
+        let (count, content) = read_leb128(content)?;
+        let mut bits = BitReader::new(content);
+        let mut words: Vec<String> = Vec::with_capacity(count.min(content.len() * 8));
+        let mut previous: Vec<u8> = Vec::new();
+
+        for _ in 0..count {
+            let prefix_length = table.prefix.read(&mut bits)? as usize;
+            if prefix_length > previous.len() || prefix_length > length {
+                return Err(DecodeError::new(format!(
+                    "invalid prefix length {prefix_length}"
+                )));
+            }
+
+            let mut word = previous[..prefix_length].to_vec();
+            while word.len() < length {
+                word.push(table.letter.read(&mut bits)?);
+            }
+
+            words.push(
+                String::from_utf8(word.to_owned())
+                    .map_err(|err| DecodeError::new(format!("invalid word: {err}")))?,
+            );
+            previous = word;
+        }
+
+        Ok(words)
+    }
+}
+
+/// Front coding of a list of words, as (prefix length, suffix) pairs.
+fn front_coding<'a>(words: &'a [&'a str]) -> impl Iterator<Item = (usize, &'a str)> {
+    let previous = std::iter::once("").chain(words.iter().copied());
+    words.iter().zip(previous).map(|(word, previous)| {
+        let prefix_length = common_prefix_length(previous, word);
+        (prefix_length, &word[prefix_length..])
+    })
+}
+
+/// Compute the lengths of a Huffman code, given the frequencies of the symbols.
+///
+/// A single symbol has a code of length 1.
+fn huffman_lengths(frequencies: &BTreeMap<u8, usize>) -> BTreeMap<u8, u8> {
+    if frequencies.len() == 1 {
+        return frequencies.keys().map(|&symbol| (symbol, 1)).collect();
+    }
+
+    // Each tree is a list of symbols, merged two by two from the least
+    // frequent ones. Ties are broken by creation order, for a deterministic
+    // code.
+    let mut trees: Vec<Vec<u8>> = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for (&symbol, &frequency) in frequencies.iter() {
+        heap.push(Reverse((frequency, trees.len())));
+        trees.push(vec![symbol]);
+    }
+
+    let mut lengths: BTreeMap<u8, u8> = frequencies.keys().map(|&symbol| (symbol, 0)).collect();
+
+    while heap.len() > 1 {
+        let Reverse((f1, t1)) = heap.pop().unwrap();
+        let Reverse((f2, t2)) = heap.pop().unwrap();
+
+        let mut merged = std::mem::take(&mut trees[t1]);
+        merged.append(&mut trees[t2]);
+
+        for symbol in merged.iter() {
+            *lengths.get_mut(symbol).unwrap() += 1;
+        }
+
+        heap.push(Reverse((f1 + f2, trees.len())));
+        trees.push(merged);
+    }
+
+    lengths
+}
+
+/// Code tables trained for a build.
+///
+/// The table file lists, for the prefix lengths then for the letters, the
+/// number of symbols (one byte) followed by `(symbol, code length)` byte
+/// pairs.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct CodeTable {
+    /// Code of the prefix lengths.
+    prefix: Code,
+    /// Code of the suffix letters.
+    letter: Code,
+}
+
+impl CodeTable {
+    fn to_bytes(&self) -> Result<Vec<u8>, std::io::Error> {
+        let mut bytes = Vec::new();
+        for code in [&self.prefix, &self.letter] {
+            let n = u8::try_from(code.symbols.len()).map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("too many symbols ({}) in a code table", code.symbols.len()),
+                )
+            })?;
+            bytes.push(n);
+            for (symbol, length) in code.symbols.iter().zip(code.lengths.iter()) {
+                bytes.extend([*symbol, *length]);
+            }
+        }
+        Ok(bytes)
+    }
+
+    fn from_bytes(mut bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut codes = Vec::new();
+
+        for _ in 0..2 {
+            let (&n, rest) = bytes
+                .split_first()
+                .ok_or_else(|| DecodeError::new("truncated code table"))?;
+            let n = n as usize;
+            let pairs = rest
+                .get(..2 * n)
+                .ok_or_else(|| DecodeError::new("truncated code table"))?;
+            let lengths: BTreeMap<u8, u8> =
+                pairs.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+            if lengths.values().any(|&length| length == 0 || length > 64) {
+                return Err(DecodeError::new("invalid code length"));
+            }
+            codes.push(Code::new(&lengths));
+            bytes = &rest[2 * n..];
+        }
+
+        if !bytes.is_empty() {
+            return Err(DecodeError::new("unexpected data after the code table"));
+        }
+
+        let letter = codes.pop().unwrap_or_default();
+        let prefix = codes.pop().unwrap_or_default();
+
+        Ok(Self { prefix, letter })
+    }
+}
+
+/// Canonical prefix code.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Code {
+    /// Symbols, sorted by code length then by value.
+    symbols: Vec<u8>,
+    /// Code length of each symbol.
+    lengths: Vec<u8>,
+    /// Code of each symbol.
+    codes: BTreeMap<u8, (u64, u8)>,
+}
+
+impl Code {
+    /// Create the canonical code given the code lengths of the symbols.
+    fn new(lengths: &BTreeMap<u8, u8>) -> Self {
+        let mut symbols: Vec<(u8, u8)> = lengths
+            .iter()
+            .map(|(&symbol, &length)| (length, symbol))
+            .collect();
+        symbols.sort();
+
+        let mut codes = BTreeMap::new();
+        let mut code: u64 = 0;
+        let mut previous_length = symbols.first().map_or(0, |(length, _)| *length);
+
+        for &(length, symbol) in symbols.iter() {
+            code <<= length - previous_length;
+            codes.insert(symbol, (code, length));
+            code += 1;
+            previous_length = length;
+        }
+
+        Self {
+            symbols: symbols.iter().map(|(_, symbol)| *symbol).collect(),
+            lengths: symbols.iter().map(|(length, _)| *length).collect(),
+            codes,
+        }
+    }
+
+    /// Write the code of a symbol.
+    fn write(&self, bits: &mut BitWriter, symbol: u8) -> Result<(), std::io::Error> {
+        let (code, length) = self.codes.get(&symbol).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("symbol {symbol} is not in the code table"),
+            )
+        })?;
+        bits.write(*code, *length);
+        Ok(())
+    }
+
+    /// Read a symbol.
+    fn read(&self, bits: &mut BitReader) -> Result<u8, DecodeError> {
+        let mut code: u64 = 0;
+        let mut first: u64 = 0;
+        let mut index = 0;
+        let mut length = 0;
+
+        while index < self.symbols.len() {
+            code = (code << 1) | bits.read()? as u64;
+            first <<= 1;
+            length += 1;
+
+            // Number of symbols with the current length
+            let count = self.lengths[index..]
+                .iter()
+                .take_while(|&&l| l == length)
+                .count() as u64;
+
+            if code < first + count {
+                return Ok(self.symbols[index + (code - first) as usize]);
+            }
+
+            first += count;
+            index += count as usize;
+        }
+
+        Err(DecodeError::new("invalid code"))
+    }
+}
+
+/// Writer of bits, most significant bit first.
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// Number of bits used in the last byte (0 to 7).
+    used: u8,
+}
+
+impl BitWriter {
+    fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes, used: 0 }
+    }
+
+    fn write(&mut self, code: u64, length: u8) {
+        for i in (0..length).rev() {
+            if self.used == 0 {
+                self.bytes.push(0);
+            }
+            let bit = ((code >> i) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (7 - self.used);
+            self.used = (self.used + 1) % 8;
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reader of bits, most significant bit first.
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// Position of the next bit.
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn read(&mut self) -> Result<u8, DecodeError> {
+        let byte = self
+            .bytes
+            .get(self.position / 8)
+            .ok_or_else(|| DecodeError::new("truncated bit stream"))?;
+        let bit = (byte >> (7 - self.position % 8)) & 1;
+        self.position += 1;
+        Ok(bit)
+    }
+}
+
+/// Write an unsigned integer in LEB128.
+fn write_leb128(bytes: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// Read an unsigned integer in LEB128, and return the remaining bytes.
+fn read_leb128(bytes: &[u8]) -> Result<(usize, &[u8]), DecodeError> {
+    let mut value: usize = 0;
+
+    for (index, byte) in bytes.iter().enumerate() {
+        let shift = 7 * index;
+        if shift >= usize::BITS as usize {
+            break;
+        }
+        value |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, &bytes[index + 1..]));
+        }
+    }
+
+    Err(DecodeError::new("invalid word count"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_code() {
+        let frequencies = BTreeMap::from([(b'a', 10), (b'b', 1), (b'c', 1), (b'd', 5)]);
+        let lengths = huffman_lengths(&frequencies);
+        assert_eq!(
+            lengths,
+            BTreeMap::from([(b'a', 1), (b'b', 3), (b'c', 3), (b'd', 2)])
+        );
+
+        let code = Code::new(&lengths);
+        assert_eq!(code.codes[&b'a'], (0b0, 1));
+        assert_eq!(code.codes[&b'd'], (0b10, 2));
+        assert_eq!(code.codes[&b'b'], (0b110, 3));
+        assert_eq!(code.codes[&b'c'], (0b111, 3));
+    }
+
+    #[test]
+    fn round_trip() {
+        let dictionaries = vec![
+            vec!["aient", "aimes", "oient", "oimes"],
+            vec!["abcde", "abcdf", "zzzzz"],
+            vec!["aaaaa"],
+        ];
+        let (encoder, table) = HuffmanEncoder::default().train(&dictionaries).unwrap();
+        let decoder = HuffmanEncoder::default().load(&table).unwrap();
+
+        for words in dictionaries.iter() {
+            let mut content = Vec::new();
+            encoder.encode(&mut content, words).unwrap();
+            assert_eq!(decoder.decode(&content, 5).unwrap(), *words);
+        }
+
+        // The letters are not in the table
+        assert!(encoder.encode(&mut Vec::new(), &["xxxxx"]).is_err());
+        // The encoder is not trained
+        assert!(HuffmanEncoder::default()
+            .encode(&mut Vec::new(), &["aient"])
+            .is_err());
+    }
+
+    #[test]
+    fn long_prefixes() {
+        // Prefix lengths from 0 to 255: too many symbols for the table
+        let words: Vec<String> = (0..=256)
+            .rev()
+            .map(|k| format!("{}{}", "a".repeat(k), "b".repeat(257 - k)))
+            .collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let (encoder, _) = HuffmanEncoder::default()
+            .train(std::slice::from_ref(&words))
+            .unwrap();
+        assert!(encoder.encode(&mut Vec::new(), &words).is_err());
+
+        // Prefix lengths above 255
+        let words: Vec<String> = (0..300)
+            .rev()
+            .map(|k| format!("{}{}", "a".repeat(k), "b".repeat(300 - k)))
+            .collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let (encoder, _) = HuffmanEncoder::default()
+            .train(std::slice::from_ref(&words))
+            .unwrap();
+        assert!(encoder.encode(&mut Vec::new(), &words).is_err());
+    }
+
+    #[test]
+    fn leb128() {
+        for value in [0, 1, 127, 128, 300, 1 << 20] {
+            let mut bytes = Vec::new();
+            write_leb128(&mut bytes, value);
+            bytes.push(42);
+            assert_eq!(read_leb128(&bytes).unwrap(), (value, &[42][..]));
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use crate::{config::Config, lexique::Cgram, source::Entry};
+
+/// Filter applied by the build to the entries of the word sources.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    /// Grammatical category in `dictionary_cgram`, or unknown outside of
+    /// Lexique.
+    Cgram,
+    /// Length between `min_length` and `max_length`.
+    Length,
+    /// Only ascii letters, which removes compound words.
+    Ascii,
+    /// Frequencies above `challenge_min_freqfilms2` and
+    /// `challenge_min_freqlivres`.
+    ChallengeFrequency,
+    /// Grammatical category in `challenge_cgram`.
+    ChallengeCgram,
+    /// Verbal form matching `challenge_infover`, for verbs.
+    ChallengeInfover,
+}
+
+impl Filter {
+    /// Filters of the words of the dictionary.
+    pub const DICTIONARY: [Filter; 3] = [Filter::Cgram, Filter::Length, Filter::Ascii];
+
+    /// Filters of the challenge words, among the words of the dictionary.
+    pub const CHALLENGE: [Filter; 3] = [
+        Filter::ChallengeFrequency,
+        Filter::ChallengeCgram,
+        Filter::ChallengeInfover,
+    ];
+
+    /// Name of the filter.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cgram => "cgram",
+            Self::Length => "length",
+            Self::Ascii => "ascii",
+            Self::ChallengeFrequency => "challenge frequency",
+            Self::ChallengeCgram => "challenge cgram",
+            Self::ChallengeInfover => "challenge infover",
+        }
+    }
+
+    /// Check if an entry passes the filter.
+    pub fn accepts(&self, config: &Config, entry: &Entry) -> bool {
+        match self {
+            // If cgram is unknown, keep the word, unless it is a row of Lexique
+            // without grammatical category
+            Self::Cgram => match &entry.cgram {
+                Some(cgram) => config.dictionary_cgram.contains(cgram),
+                None => entry.record.is_none(),
+            },
+            Self::Length => {
+                let length = entry.word.chars().count();
+                length >= config.min_length && length <= config.max_length
+            },
+            Self::Ascii => entry.word.chars().all(|c| c.is_ascii_alphabetic()),
+            // If frequencies are unknown, the word is not a challenge
+            Self::ChallengeFrequency => {
+                entry.freqfilms2.unwrap_or(0.0) >= config.challenge_min_freqfilms2
+                    && entry.freqlivres.unwrap_or(0.0) >= config.challenge_min_freqlivres
+            },
+            Self::ChallengeCgram => entry
+                .cgram
+                .as_ref()
+                .is_some_and(|cgram| config.challenge_cgram.contains(cgram)),
+            Self::ChallengeInfover => {
+                entry.cgram != Some(Cgram::Ver)
+                    || config.challenge_infover.is_empty()
+                    || entry.infover.0.iter().any(|infover| {
+                        config
+                            .challenge_infover
+                            .iter()
+                            .any(|pattern| infover.matches(pattern))
+                    })
+            },
+        }
+    }
+
+    /// Describe the values of an entry checked by the filter.
+    fn describe(&self, config: &Config, entry: &Entry) -> String {
+        let cgram = match (&entry.cgram, &entry.record) {
+            (Some(cgram), _) => cgram.as_str(),
+            (None, Some(_)) => "missing in Lexique",
+            (None, None) => "unknown",
+        };
+        let frequency = |value: Option<f64>| value.map_or("unknown".to_owned(), |v| v.to_string());
+
+        match self {
+            Self::Cgram | Self::ChallengeCgram => cgram.to_owned(),
+            Self::Length => format!(
+                "{} letters, from {} to {}",
+                entry.word.chars().count(),
+                config.min_length,
+                config.max_length
+            ),
+            Self::Ascii => format!("{:?}", entry.word),
+            Self::ChallengeFrequency => format!(
+                "freqfilms2 {} (min {}), freqlivres {} (min {})",
+                frequency(entry.freqfilms2),
+                config.challenge_min_freqfilms2,
+                frequency(entry.freqlivres),
+                config.challenge_min_freqlivres
+            ),
+            Self::ChallengeInfover if entry.cgram != Some(Cgram::Ver) => "not a verb".to_owned(),
+            Self::ChallengeInfover if entry.infover.0.is_empty() => "none".to_owned(),
+            Self::ChallengeInfover => entry
+                .infover
+                .0
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+/// Verdict of a filter on an entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Verdict {
+    /// Filter.
+    pub filter: Filter,
+    /// Whether the entry passes the filter.
+    pub accepted: bool,
+    /// Values of the entry checked by the filter.
+    pub detail: String,
+}
+
+/// Verdicts of the filters on an entry of the word sources.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryExplanation {
+    /// Original spelling.
+    pub ortho: String,
+    /// Grammatical category, if known.
+    pub cgram: Option<Cgram>,
+    /// Lemma, if known.
+    pub lemme: Option<String>,
+    /// Verdicts of the dictionary filters, then of the challenge filters.
+    pub verdicts: Vec<Verdict>,
+}
+
+impl EntryExplanation {
+    /// Apply all filters to an entry.
+    pub fn new(config: &Config, entry: &Entry) -> Self {
+        let verdicts = Filter::DICTIONARY
+            .iter()
+            .chain(Filter::CHALLENGE.iter())
+            .map(|filter| Verdict {
+                filter: *filter,
+                accepted: filter.accepts(config, entry),
+                detail: filter.describe(config, entry),
+            })
+            .collect();
+
+        Self {
+            ortho: entry.ortho.clone(),
+            cgram: entry.cgram,
+            lemme: entry.lemme.clone(),
+            verdicts,
+        }
+    }
+
+    /// Check if the entry passes the filters of the dictionary.
+    pub fn in_dictionary(&self) -> bool {
+        self.passes(&Filter::DICTIONARY)
+    }
+
+    /// Check if the entry passes the filters of the dictionary and of the
+    /// challenges.
+    pub fn is_challenge(&self) -> bool {
+        self.in_dictionary() && self.passes(&Filter::CHALLENGE)
+    }
+
+    fn passes(&self, filters: &[Filter]) -> bool {
+        self.verdicts
+            .iter()
+            .filter(|verdict| filters.contains(&verdict.filter))
+            .all(|verdict| verdict.accepted)
+    }
+}
+
+/// Reasons why a word is accepted or rejected by the build.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation {
+    /// Normalized word.
+    pub word: String,
+    /// Entries of the word sources whose normalized word matches.
+    pub entries: Vec<EntryExplanation>,
+    /// Rows of the word sources that cannot be parsed, whose normalized word
+    /// matches.
+    pub skipped_rows: Vec<String>,
+    /// Name of the sub-dictionary file of the word, if it is in the
+    /// dictionary (without extension with the `auto` encoder).
+    pub file: Option<String>,
+}
+
+impl Explanation {
+    /// Check if the word is a challenge candidate.
+    pub fn is_challenge(&self) -> bool {
+        self.entries.iter().any(EntryExplanation::is_challenge)
+    }
+}
+
+/// Verdicts of each entry, followed by the sub-dictionary file and whether the
+/// word is a challenge candidate.
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.word)?;
+
+        if self.entries.is_empty() && self.skipped_rows.is_empty() {
+            writeln!(f, "  no entry in the word sources")?;
+        }
+
+        for row in self.skipped_rows.iter() {
+            writeln!(f, "  skipped row: {row}")?;
+        }
+
+        for entry in self.entries.iter() {
+            let cgram = entry.cgram.as_ref().map_or("unknown", Cgram::as_str);
+            write!(f, "  {} ({cgram}", entry.ortho)?;
+            if let Some(lemme) = &entry.lemme {
+                write!(f, ", lemma {lemme}")?;
+            }
+            writeln!(f, ")")?;
+
+            for verdict in entry.verdicts.iter() {
+                let accepted = if verdict.accepted {
+                    "accepted"
+                } else {
+                    "rejected"
+                };
+                writeln!(
+                    f,
+                    "    {accepted:<8}  {:<19}  {}",
+                    verdict.filter.name(),
+                    verdict.detail
+                )?;
+            }
+        }
+
+        match &self.file {
+            Some(file) => writeln!(f, "dictionary: {file}")?,
+            None => writeln!(f, "dictionary: rejected")?,
+        }
+        let challenge = if self.is_challenge() { "yes" } else { "no" };
+        writeln!(f, "challenge candidate: {challenge}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexique::VecInfover;
+
+    fn entry(word: &str, cgram: Option<Cgram>, frequency: f64, infover: &[&str]) -> Entry {
+        Entry {
+            word: word.to_owned(),
+            cgram,
+            freqfilms2: Some(frequency),
+            freqlivres: Some(frequency),
+            infover: VecInfover(infover.iter().map(|s| s.parse().unwrap()).collect()),
+            ..Entry::new(word)
+        }
+    }
+
+    fn rejected(config: &Config, entry: &Entry) -> Vec<&'static str> {
+        EntryExplanation::new(config, entry)
+            .verdicts
+            .iter()
+            .filter(|verdict| !verdict.accepted)
+            .map(|verdict| verdict.filter.name())
+            .collect()
+    }
+
+    #[test]
+    fn filters() {
+        let config = Config::default();
+
+        let maison = entry("maison", Some(Cgram::Nom), 10.0, &[]);
+        assert_eq!(rejected(&config, &maison), Vec::<&str>::new());
+        assert!(EntryExplanation::new(&config, &maison).is_challenge());
+
+        // Unknown cgram is accepted in the dictionary, but not as a challenge
+        let maison = entry("maison", None, 10.0, &[]);
+        assert_eq!(rejected(&config, &maison), ["challenge cgram"]);
+        assert!(EntryExplanation::new(&config, &maison).in_dictionary());
+
+        // A row of Lexique without cgram is rejected
+        let maison = Entry {
+            record: Some(Box::default()),
+            ..entry("maison", None, 10.0, &[])
+        };
+        assert_eq!(rejected(&config, &maison), ["cgram", "challenge cgram"]);
+
+        let chez = entry("chez", Some(Cgram::Pre), 10.0, &[]);
+        assert_eq!(
+            rejected(&config, &chez),
+            ["cgram", "length", "challenge cgram"]
+        );
+        assert!(!EntryExplanation::new(&config, &chez).is_challenge());
+
+        let compound = entry("arc-en-ciel", Some(Cgram::Nom), 0.5, &[]);
+        assert_eq!(
+            rejected(&config, &compound),
+            ["length", "ascii", "challenge frequency"]
+        );
+
+        let aimons = entry(
+            "aimons",
+            Some(Cgram::Ver),
+            10.0,
+            &["ind:pre:1p", "imp:pre:1p"],
+        );
+        assert_eq!(rejected(&config, &aimons), ["challenge infover"]);
+        assert!(EntryExplanation::new(&config, &aimons).in_dictionary());
+
+        let aimer = entry("aimer", Some(Cgram::Ver), 10.0, &["inf"]);
+        assert_eq!(rejected(&config, &aimer), ["length"]);
+    }
+
+    #[test]
+    fn display() {
+        let config = Config::default();
+        let explanation = Explanation {
+            word: "aimons".to_owned(),
+            entries: vec![EntryExplanation::new(
+                &config,
+                &Entry {
+                    lemme: Some("aimer".to_owned()),
+                    ..entry(
+                        "aimons",
+                        Some(Cgram::Ver),
+                        10.0,
+                        &["ind:pre:1p", "imp:pre:1p"],
+                    )
+                },
+            )],
+            skipped_rows: Vec::new(),
+            file: Some("6a.txt".to_owned()),
+        };
+
+        assert_eq!(
+            explanation.to_string(),
+            "aimons\n\
+             \x20 aimons (VER, lemma aimer)\n\
+             \x20   accepted  cgram                VER\n\
+             \x20   accepted  length               6 letters, from 6 to 10\n\
+             \x20   accepted  ascii                \"aimons\"\n\
+             \x20   accepted  challenge frequency  freqfilms2 10 (min 1), freqlivres 10 (min 1)\n\
+             \x20   accepted  challenge cgram      VER\n\
+             \x20   rejected  challenge infover    ind:pre:1p, imp:pre:1p\n\
+             dictionary: 6a.txt\n\
+             challenge candidate: no\n"
+        );
+
+        let explanation = Explanation {
+            word: "maison".to_owned(),
+            entries: Vec::new(),
+            skipped_rows: vec!["line 5, column cgram: invalid value \"XYZ\"".to_owned()],
+            file: None,
+        };
+
+        assert_eq!(
+            explanation.to_string(),
+            "maison\n\
+             \x20 skipped row: line 5, column cgram: invalid value \"XYZ\"\n\
+             dictionary: rejected\n\
+             challenge candidate: no\n"
+        );
+    }
+}
@@ -1,11 +1,18 @@
-use serde::Deserialize;
+use std::{fs::File, io::Read, path::Path};
+
+use serde::{Deserialize, Serialize};
 
 /// Parse error.
+///
+/// Errors of [`Reader`] carry the line number, the column name, the raw value
+/// of the field and the spelling of the row, when known.
 #[derive(Debug)]
 pub struct ParseError {
-    name: String,
-    token: String,
-    context: String,
+    line: Option<u64>,
+    column: Option<String>,
+    value: Option<String>,
+    ortho: Option<String>,
+    message: String,
 }
 
 impl ParseError {
@@ -15,28 +22,247 @@ impl ParseError {
         S2: AsRef<str>,
         S3: AsRef<str>,
     {
+        let (name, token, context) = (name.as_ref(), token.as_ref(), context.as_ref());
+        Self {
+            line: None,
+            column: None,
+            value: Some(context.to_owned()),
+            ortho: None,
+            message: format!("invalid {name} {token:?} in {context:?}"),
+        }
+    }
+
+    /// Set the line number.
+    pub fn with_line(self, line: u64) -> Self {
         Self {
-            name: name.as_ref().to_owned(),
-            token: token.as_ref().to_owned(),
-            context: context.as_ref().to_owned(),
+            line: Some(line),
+            ..self
         }
     }
+
+    /// Set the name of the column.
+    pub fn with_column<S>(self, column: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            column: Some(column.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    /// Set the spelling of the row.
+    pub fn with_ortho<S>(self, ortho: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            ortho: Some(ortho.as_ref().to_owned()),
+            ..self
+        }
+    }
+
+    /// Line number in the database, starting from 1 (the header).
+    pub fn line(&self) -> Option<u64> {
+        self.line
+    }
+
+    /// Name of the column.
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    /// Raw value of the field.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Spelling of the row (the `ortho` column).
+    pub fn ortho(&self) -> Option<&str> {
+        self.ortho.as_deref()
+    }
+
+    /// Description of the error, without its position.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "invalid {} {:?} in {:?}",
-            self.name, self.token, self.context
-        )
+        match (self.line, &self.column) {
+            (Some(line), Some(column)) => write!(f, "line {line}, column {column}: ")?,
+            (Some(line), None) => write!(f, "line {line}: ")?,
+            (None, Some(column)) => write!(f, "column {column}: ")?,
+            (None, None) => {},
+        }
+        write!(f, "{}", self.message)
     }
 }
 
 impl std::error::Error for ParseError {}
 
+/// Streaming reader of the Lexique 3.83 database in TSV format.
+///
+/// Records are parsed one row at a time. A row that cannot be parsed yields a
+/// [`ParseError`], and the next rows are still read.
+#[derive(Debug)]
+pub struct Reader<R> {
+    reader: csv::Reader<R>,
+    headers: Option<csv::StringRecord>,
+    row: csv::StringRecord,
+    done: bool,
+}
+
+impl Reader<File> {
+    /// Open a database file.
+    pub fn from_path<P>(path: P) -> std::io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::new(File::open(path)?))
+    }
+}
+
+impl<R> Reader<R>
+where
+    R: Read,
+{
+    /// Create a reader of a database, whose first line is the header.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: csv::ReaderBuilder::new()
+                .delimiter(b'\t')
+                .from_reader(reader),
+            headers: None,
+            row: csv::StringRecord::new(),
+            done: false,
+        }
+    }
+
+    /// Read the next record.
+    fn read(&mut self) -> Result<Option<Record>, csv::Error> {
+        if self.headers.is_none() {
+            self.headers = Some(self.reader.headers()?.clone());
+        }
+
+        if !self.reader.read_record(&mut self.row)? {
+            return Ok(None);
+        }
+
+        self.row.deserialize(self.headers.as_ref()).map(Some)
+    }
+
+    /// Convert an error of the CSV parser, with the position of the field.
+    fn error(&self, err: csv::Error) -> ParseError {
+        let line = err.position().map(csv::Position::line);
+        let ortho = self
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.iter().position(|column| column == "ortho"))
+            .and_then(|index| self.row.get(index))
+            .map(str::to_owned);
+
+        let err = match err.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => err,
+            csv::ErrorKind::UnequalLengths {
+                expected_len, len, ..
+            } => {
+                return ParseError {
+                    line,
+                    column: None,
+                    value: None,
+                    ortho,
+                    message: format!("found {len} fields, expected {expected_len}"),
+                };
+            },
+            _ => {
+                return ParseError {
+                    line,
+                    column: None,
+                    value: None,
+                    ortho,
+                    message: err.to_string(),
+                };
+            },
+        };
+
+        // Errors of the custom parsers do not tell their field
+        let index = err
+            .field()
+            .map(|index| index as usize)
+            .or_else(|| self.invalid_field());
+        let column = index
+            .zip(self.headers.as_ref())
+            .and_then(|(index, headers)| headers.get(index))
+            .map(str::to_owned);
+        let value = index
+            .and_then(|index| self.row.get(index))
+            .map(str::to_owned);
+
+        // Messages of the custom parsers already hold the value
+        let message = match (err.kind(), &value) {
+            (csv::DeserializeErrorKind::Message(message), _) => message.clone(),
+            (kind, Some(value)) => format!("{kind} in {value:?}"),
+            (kind, None) => kind.to_string(),
+        };
+
+        ParseError {
+            line,
+            column,
+            value,
+            ortho,
+            message,
+        }
+    }
+
+    /// Find the first field of the row rejected by a custom parser.
+    fn invalid_field(&self) -> Option<usize> {
+        let headers = self.headers.as_ref()?;
+
+        headers
+            .iter()
+            .zip(self.row.iter())
+            .position(|(column, value)| match column {
+                // Empty values are missing values
+                _ if value.is_empty() => false,
+                "cgram" => parse_cgram(value).is_err(),
+                "genre" => parse_genre(value).is_err(),
+                "nombre" => parse_nombre(value).is_err(),
+                "infover" => parse_vec_infover(value).is_err(),
+                _ => false,
+            })
+    }
+}
+
+impl<R> Iterator for Reader<R>
+where
+    R: Read,
+{
+    type Item = Result<Record, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.read() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(err) => {
+                // Stop after errors that cannot be skipped
+                self.done = err.is_io_error() || self.headers.is_none();
+                Some(Err(self.error(err)))
+            },
+        }
+    }
+}
+
 /// Catégorie grammaticale
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Cgram {
     /// Adjectif
     Adj,
@@ -85,7 +311,7 @@ pub enum Cgram {
 }
 
 /// Genre
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Genre {
     /// Masculin
     M,
@@ -94,7 +320,7 @@ pub enum Genre {
 }
 
 /// Nombre
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Nombre {
     /// Singulier
     S,
@@ -117,8 +343,26 @@ pub struct Infover {
     pub personne: Option<InfoverPersonne>,
 }
 
+impl Infover {
+    /// Check if the item matches a pattern.
+    /// Missing `temps` and `personne` in the pattern match any value.
+    pub fn matches(&self, pattern: &Infover) -> bool {
+        self.mode == pattern.mode
+            && pattern.temps.is_none_or(|t| self.temps == Some(t))
+            && pattern.personne.is_none_or(|p| self.personne == Some(p))
+    }
+}
+
+impl std::str::FromStr for Infover {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_infover(s)
+    }
+}
+
 /// Mode (information complémentaire sur les verbes)
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum InfoverMode {
     /// Indicatif
     Ind,
@@ -135,7 +379,7 @@ pub enum InfoverMode {
 }
 
 /// Temps (information complémentaire sur les verbes)
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum InfoverTemps {
     /// Présent
     Pre,
@@ -148,7 +392,7 @@ pub enum InfoverTemps {
 }
 
 /// Personne (information complémentaire sur les verbes)
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum InfoverPersonne {
     /// 1ère personne du singulier
     S1,
@@ -169,6 +413,10 @@ pub enum InfoverPersonne {
 pub struct VecCgram(pub Vec<Cgram>);
 
 /// Database record of Lexique 3.83
+///
+/// Columns that the generator does not use are read leniently: a malformed
+/// value gives the default value (0, empty or missing) instead of rejecting
+/// the row.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq)]
 pub struct Record {
     /// Mot
@@ -184,8 +432,10 @@ pub struct Record {
     /// Nombre
     pub nombre: Option<Nombre>,
     /// Fréquence du lemme par million selon le corpus de films
+    #[serde(deserialize_with = "lenient")]
     pub freqlemfilms2: f64,
     /// Fréquence du lemme par million selon le corpus de livres
+    #[serde(deserialize_with = "lenient")]
     pub freqlemlivres: f64,
     /// Fréquence par million selon le corpus de films
     pub freqfilms2: f64,
@@ -194,12 +444,16 @@ pub struct Record {
     /// Informations verbales
     pub infover: VecInfover,
     /// Nombre d'homographes
+    #[serde(deserialize_with = "lenient")]
     pub nbhomogr: usize,
     /// Nombre d'homophones
+    #[serde(deserialize_with = "lenient")]
     pub nbhomoph: usize,
     //// Nombre de lettres
+    #[serde(deserialize_with = "lenient")]
     pub nblettres: usize,
     /// Nombre de phonèmes
+    #[serde(deserialize_with = "lenient")]
     pub nbphons: usize,
     /// Structure orthographique
     pub cvcv: String,
@@ -208,10 +462,12 @@ pub struct Record {
     /// Nombre de voisins orthographiques
     pub voisorth: usize,
     /// Nombre de voisins phonologiques
+    #[serde(deserialize_with = "lenient")]
     pub voisphon: usize,
     /// Point d'unicité orthographique
     pub puorth: usize,
     /// Point d'unicité phonologique
+    #[serde(deserialize_with = "lenient")]
     pub puphon: usize,
     /// Syllabation
     pub syll: String,
@@ -227,21 +483,139 @@ pub struct Record {
     /// Représentation orthographique syllabée
     pub orthosyll: String,
     /// Catégories grammaticales possibles de la forme orthographique
+    #[serde(deserialize_with = "lenient")]
     pub cgramortho: VecCgram,
     /// Pourcentage de personnes connaissant la définition du lemme
     pub deflem: Option<f64>,
     /// Nombre de personnes ayant répondu pour la définition de ce lemme
+    #[serde(deserialize_with = "lenient")]
     pub defobs: Option<f64>,
     /// Distance de Levenshtein orthographique
     pub old20: f64,
     /// Distance de Levenshtein phonologique
+    #[serde(deserialize_with = "lenient")]
     pub pld20: Option<f64>,
     /// Morphologie Dérivationnelle
     pub morphoder: String,
     /// Nombre de morphèmes
+    #[serde(deserialize_with = "lenient")]
     pub nbmorph: usize,
 }
 
+/// Deserialize a column that the generator does not use, with its default
+/// value if it is malformed.
+fn lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(T::deserialize(deserializer).unwrap_or_default())
+}
+
+impl Cgram {
+    /// Get the code of the category in Lexique (e.g. `"NOM"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Adj => "ADJ",
+            Self::AdjDem => "ADJ:dem",
+            Self::AdjInd => "ADJ:ind",
+            Self::AdjInt => "ADJ:int",
+            Self::AdjNum => "ADJ:num",
+            Self::AdjPos => "ADJ:pos",
+            Self::Adv => "ADV",
+            Self::ArtDef => "ART:def",
+            Self::ArtInd => "ART:ind",
+            Self::Aux => "AUX",
+            Self::Con => "CON",
+            Self::Lia => "LIA",
+            Self::Nom => "NOM",
+            Self::Ono => "ONO",
+            Self::Pre => "PRE",
+            Self::ProDem => "PRO:dem",
+            Self::ProInd => "PRO:ind",
+            Self::ProInt => "PRO:int",
+            Self::ProPer => "PRO:per",
+            Self::ProPos => "PRO:pos",
+            Self::ProRel => "PRO:rel",
+            Self::Ver => "VER",
+        }
+    }
+}
+
+impl Genre {
+    /// Get the code of the gender in Lexique (e.g. `"m"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::M => "m",
+            Self::F => "f",
+        }
+    }
+}
+
+impl Nombre {
+    /// Get the code of the number in Lexique (e.g. `"s"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::S => "s",
+            Self::P => "p",
+        }
+    }
+}
+
+impl InfoverMode {
+    /// Get the code of the mode in Lexique (e.g. `"ind"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ind => "ind",
+            Self::Cnd => "cnd",
+            Self::Sub => "sub",
+            Self::Par => "par",
+            Self::Inf => "inf",
+            Self::Imp => "imp",
+        }
+    }
+}
+
+impl InfoverTemps {
+    /// Get the code of the tense in Lexique (e.g. `"pre"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pre => "pre",
+            Self::Fut => "fut",
+            Self::Imp => "imp",
+            Self::Pas => "pas",
+        }
+    }
+}
+
+impl InfoverPersonne {
+    /// Get the code of the person in Lexique (e.g. `"3s"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::S1 => "1s",
+            Self::S2 => "2s",
+            Self::S3 => "3s",
+            Self::P1 => "1p",
+            Self::P2 => "2p",
+            Self::P3 => "3p",
+        }
+    }
+}
+
+/// Code of the verbal form in Lexique (e.g. `ind:pre:3s`).
+impl std::fmt::Display for Infover {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.mode.as_str())?;
+        if let Some(temps) = self.temps {
+            write!(f, ":{}", temps.as_str())?;
+        }
+        if let Some(personne) = self.personne {
+            write!(f, ":{}", personne.as_str())?;
+        }
+        Ok(())
+    }
+}
+
 /// Parse the `cgram` field
 fn parse_cgram<S>(s: S) -> Result<Cgram, ParseError>
 where
@@ -321,10 +695,11 @@ fn parse_infover<S>(s: S) -> Result<Infover, ParseError>
 where
     S: AsRef<str>,
 {
+    // `split` gives at least one part, which is empty for an empty item
     let parts: Vec<&str> = s.as_ref().split(':').collect();
 
     Ok(Infover {
-        mode: parts.get(0).map(parse_infover_mode).unwrap()?,
+        mode: parse_infover_mode(parts[0])?,
         temps: parts
             .get(1)
             .map(parse_infover_temps)
@@ -407,7 +782,8 @@ impl<'de> Deserialize<'de> for Cgram {
     where
         D: serde::Deserializer<'de>,
     {
-        let s: &str = Deserialize::deserialize(deserializer)?;
+        // Owned string, since configuration files may not lend their strings
+        let s: String = Deserialize::deserialize(deserializer)?;
         parse_cgram(s).map_err(serde::de::Error::custom)
     }
 }
@@ -445,6 +821,17 @@ impl<'de> Deserialize<'de> for VecInfover {
     }
 }
 
+/// Custom deserializer for an item of the `infover` field.
+impl<'de> Deserialize<'de> for Infover {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        parse_infover(s).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Custom deserializer for the `cgramortho` field.
 impl<'de> Deserialize<'de> for VecCgram {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -455,3 +842,145 @@ impl<'de> Deserialize<'de> for VecCgram {
         parse_vec_cgram(s).map_err(serde::de::Error::custom)
     }
 }
+
+/// Custom serializer for the `cgram` field.
+impl Serialize for Cgram {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Custom serializer for the `genre` field.
+impl Serialize for Genre {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Custom serializer for the `nombre` field.
+impl Serialize for Nombre {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "ortho\tphon\tlemme\tcgram\tgenre\tnombre\tfreqlemfilms2\tfreqlemlivres\t\
+        freqfilms2\tfreqlivres\tinfover\tnbhomogr\tnbhomoph\tislem\tnblettres\tnbphons\tcvcv\t\
+        p_cvcv\tvoisorth\tvoisphon\tpuorth\tpuphon\tsyll\tnbsyll\tcv-cv\torthrenv\tphonrenv\t\
+        orthosyll\tcgramortho\tdeflem\tdefobs\told20\tpld20\tmorphoder\tnbmorph";
+
+    /// Row of the database, given the values of `ortho`, `cgram` and
+    /// `infover`.
+    fn row(ortho: &str, cgram: &str, infover: &str) -> String {
+        format!(
+            "{ortho}\tphon\t{ortho}\t{cgram}\t\t\t1\t1\t2\t2\t{infover}\t1\t1\t1\t7\t1\tC\tC\t1\t1\t\
+             1\t1\t{ortho}\t1\t\t\t\t\t{cgram}\t\t\t1\t1\t{ortho}\t1"
+        )
+    }
+
+    #[test]
+    fn read_records() {
+        let database = [
+            HEADER.to_owned(),
+            row("aimer", "VER", "inf;"),
+            row("maison", "NOM", ""),
+        ]
+        .join("\n");
+
+        let records: Vec<Record> = Reader::new(database.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].ortho, "aimer");
+        assert_eq!(records[0].cgram, Some(Cgram::Ver));
+        assert_eq!(records[0].infover, VecInfover(vec!["inf".parse().unwrap()]));
+        assert_eq!(records[0].freqfilms2, 2.0);
+        assert_eq!(records[1].cgramortho, VecCgram(vec![Cgram::Nom]));
+    }
+
+    #[test]
+    fn read_errors() {
+        let database = [
+            HEADER.to_owned(),
+            row("aimer", "FOO", "inf;"),
+            row("maison", "NOM", ""),
+            row("aimai", "VER", "ind:xyz:1s;"),
+            "manger\tmɑ̃ʒe".to_owned(),
+        ]
+        .join("\n");
+
+        let results: Vec<_> = Reader::new(database.as_bytes()).collect();
+        assert_eq!(results.len(), 4);
+        assert!(results[1].is_ok());
+
+        let err = results[0].as_ref().unwrap_err();
+        assert_eq!(err.line(), Some(2));
+        assert_eq!(err.column(), Some("cgram"));
+        assert_eq!(err.value(), Some("FOO"));
+        assert_eq!(err.ortho(), Some("aimer"));
+        assert_eq!(
+            err.to_string(),
+            r#"line 2, column cgram: invalid cgram "FOO" in "FOO""#
+        );
+
+        let err = results[2].as_ref().unwrap_err();
+        assert_eq!(err.line(), Some(4));
+        assert_eq!(err.column(), Some("infover"));
+        assert_eq!(err.value(), Some("ind:xyz:1s;"));
+
+        // Missing columns
+        let err = results[3].as_ref().unwrap_err();
+        assert_eq!(err.line(), Some(5));
+        assert_eq!(err.column(), None);
+        assert_eq!(err.ortho(), Some("manger"));
+        assert_eq!(err.to_string(), "line 5: found 2 fields, expected 35");
+    }
+
+    #[test]
+    fn read_unused_columns() {
+        let headers: Vec<&str> = HEADER.split('\t').collect();
+        let maison = row("maison", "NOM", "");
+        let mut fields: Vec<&str> = maison.split('\t').collect();
+        for column in ["freqlemlivres", "nbhomogr", "pld20", "cgramortho"] {
+            let index = headers.iter().position(|h| *h == column).unwrap();
+            fields[index] = "x";
+        }
+        let database = [HEADER, &fields.join("\t")].join("\n");
+
+        // Malformed values of unused columns are read as default values
+        let record = Reader::new(database.as_bytes()).next().unwrap().unwrap();
+        assert_eq!(record.ortho, "maison");
+        assert_eq!(record.freqlemlivres, 0.0);
+        assert_eq!(record.nbhomogr, 0);
+        assert_eq!(record.pld20, None);
+        assert_eq!(record.cgramortho, VecCgram(Vec::new()));
+    }
+
+    #[test]
+    fn parse_infover_errors() {
+        assert!(parse_infover("").is_err());
+        assert!(parse_infover("ind:pre:4s").is_err());
+        assert_eq!(
+            parse_infover("ind:pre:1s").unwrap(),
+            Infover {
+                mode: InfoverMode::Ind,
+                temps: Some(InfoverTemps::Pre),
+                personne: Some(InfoverPersonne::S1),
+            }
+        );
+    }
+}
@@ -1,25 +1,50 @@
+mod bench;
+mod challenge;
+mod compress;
+mod config;
+mod difficulty;
+mod display;
 mod encoder;
-mod lexique;
+mod explain;
+pub mod lexique;
+mod pack;
+mod partition;
 mod random;
+mod schedule;
+mod source;
+mod transliterate;
 
-use std::{collections::HashSet, fs::create_dir_all, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, File},
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
 
-use rand::seq::SliceRandom;
+use rand::Rng;
+
+pub use bench::{BenchReport, EncoderBench, PartitionBench};
+use challenge::Challenge;
+pub use challenge::ChallengeFormat;
+pub use compress::Compression;
+use compress::{Precompressor, ZSTD_DICTIONARY};
+pub use config::{Config, ConfigError};
+use difficulty::{difficulty_scores, difficulty_tiers, Features};
+pub use difficulty::{Difficulty, DifficultyWeights};
+pub use encoder::{
+    BlockedFrontCodingEncoder, BloomEncoder, DecodeError, Encoder, EncoderRegistry, AUTO_ENCODER,
+};
+pub use explain::{EntryExplanation, Explanation, Filter, Verdict};
+use lexique::ParseError;
+pub use pack::{write_pack, PackEntry, PackReader};
+use partition::partition;
+pub use partition::{Partition, PartitionIndex, PartitionScheme, PARTITION_INDEX};
 use random::SquaresRng;
-use serde::Deserialize;
-
-/// Build configuration.
-#[derive(Clone, Debug)]
-pub struct Config {
-    pub database_path: PathBuf,
-    pub encoder_name: String,
-    pub output_path: PathBuf,
-    pub challenge_dir: PathBuf,
-    pub dictionary_dir: PathBuf,
-    pub write_challenge: bool,
-    pub write_dictionary: bool,
-    pub random_seed: u64,
-}
+use schedule::{Candidate, Constraints, Daily, ScheduleLock, Scheduler};
+use source::{read_sources, LexiqueSource};
+pub use source::{Entry, Merge, SourceConfig, WordSource};
+use transliterate::Transliterator;
+pub use transliterate::{LigaturePolicy, TransliterationError};
 
 /// Custom error.
 #[derive(Debug)]
@@ -33,94 +58,43 @@ impl std::fmt::Display for CustomError {
 
 impl std::error::Error for CustomError {}
 
-/// Partial database record.
-#[derive(Clone, Debug, Deserialize)]
-struct Record {
-    /// Normalized word
-    #[serde(skip_deserializing)]
-    word: String,
-    /// Mot
-    ortho: String,
-    /// Classe grammaticale
-    cgram: Option<lexique::Cgram>,
-    /// Fréquence par million selon le corpus de films
-    freqfilms2: f64,
-    /// Fréquence par million selon le corpus de livres
-    freqlivres: f64,
-    /// Informations verbales
-    infover: lexique::VecInfover,
-}
-
 /// Build the static files.
 ///
 /// # Output structure
 ///
 /// - `/dictionary/{length}{letter}.txt`: List of accepted words of length
-///   `{length}` and starting with `{letter}`.
+///   `{length}` and starting with `{letter}`, without this letter. The names
+///   depend on the partitioning scheme (see [`PartitionScheme`]).
+/// - `/dictionary/index.json`: Partition index, which lists the
+///   sub-dictionaries with the words they cover (see [`PartitionIndex`]).
 /// - `/challenge-count.txt`: Number of challenges.
-/// - `/challenge/{id}.txt`: Solution of challenge identified by `{id}`.
+/// - `/challenge-{difficulty}.txt`: Indices of the challenges of difficulty
+///   tier `{difficulty}` (`easy`, `medium` or `hard`), one per line.
+/// - `/challenge/{id}.txt`: Solution of challenge identified by `{id}`, followed
+///   by its display form and its difficulty tier on the next lines.
+/// - `/challenge/{id}.json`: Solution, display form and lexical metadata of
+///   challenge identified by `{id}`, if the JSON format is chosen.
 pub fn build(config: Config) -> anyhow::Result<()> {
-    // Minimum word length
-    let min_length: usize = 6;
-
-    // Maximum word length
-    let max_length: usize = 10;
-
-    // Allowed grammatical categories in dictionary
-    let dictionary_cgram = [
-        // Adjectif
-        lexique::Cgram::Adj,
-        // Adverbe
-        lexique::Cgram::Adv,
-        // Nom commun
-        lexique::Cgram::Nom,
-        // Verbe
-        lexique::Cgram::Ver,
-    ];
+    let encoders = default_encoders(&config);
+    build_with_encoders(config, &encoders)
+}
 
-    // Number of daily challenges to generate
-    let num_daily_challenges = 365 * 2;
-
-    // Read and pre-filter the database
-    let database: Vec<Record> = csv::ReaderBuilder::new()
-        .delimiter(b'\t')
-        .from_path(config.database_path)?
-        .deserialize::<Record>()
-        .filter(|result| {
-            // Keep words according to their grammatical categories
-            // If error, return false
-            result.as_ref().map_or(false, |record| {
-                // If cgram is None, return false
-                record
-                    .cgram
-                    .as_ref()
-                    .map(|v| dictionary_cgram.contains(v))
-                    .unwrap_or(false)
-            })
-        })
-        .map(|result| {
-            result.map(|record| {
-                // Get normalized words (ascii lowercase)
-                Record {
-                    word: normalize(&record.ortho),
-                    ..record
-                }
-            })
-        })
-        .filter(|result| {
-            result.as_ref().map_or(false, |record| {
-                // Keep words according to their length
-                let length = record.word.chars().count();
-                length >= min_length && length <= max_length
-            })
-        })
-        .filter(|result| {
-            result.as_ref().map_or(false, |record| {
-                // Remove compound words
-                record.word.chars().all(|c| c.is_ascii_alphabetic())
-            })
-        })
-        .collect::<Result<_, _>>()?;
+/// Build the static files, with the dictionary encoder taken from a registry.
+///
+/// See [`build`].
+pub fn build_with_encoders(config: Config, encoders: &EncoderRegistry) -> anyhow::Result<()> {
+    config.validate()?;
+
+    // No encoder with `auto`, which picks one per sub-dictionary
+    let auto = config.encoder_name == AUTO_ENCODER;
+    let encoder = if auto {
+        None
+    } else {
+        Some(find_encoder(encoders, &config.encoder_name)?)
+    };
+
+    let transliterator = Transliterator::new(config.transliteration.clone(), config.ligatures);
+    let database = read_database(&config, &transliterator)?;
 
     // Dictionary: all words from the database
     let dictionary: Vec<String> = database
@@ -132,165 +106,827 @@ pub fn build(config: Config) -> anyhow::Result<()> {
     let dictionary: Vec<String> = sort_dedup(dictionary);
 
     // Challenge list: words from a subset of the database
-    let challenges: Vec<String> = database
+    let challenge_records: Vec<&Entry> = database
         .iter()
         .filter(|record| {
-            // Keep words according to their frequency in movies and books
-            record.freqfilms2 >= 1.0 && record.freqlivres >= 1.0
+            // Keep words according to their frequency in movies and books,
+            // their grammatical category and their verbal forms
+            Filter::CHALLENGE
+                .iter()
+                .all(|filter| filter.accepts(&config, record))
         })
-        .filter(|record| {
-            // Keep words according to their grammatical category
-            match record.cgram {
-                // Nom commun
-                Some(lexique::Cgram::Nom) => true,
-                // Verbe
-                Some(lexique::Cgram::Ver) => record.infover.0.iter().any(|infover| {
-                    match infover.mode {
-                        // Infinitif
-                        lexique::InfoverMode::Inf => true,
-                        // Participe
-                        lexique::InfoverMode::Par => match infover.temps {
-                            // Participe présent
-                            Some(lexique::InfoverTemps::Pre) => true,
-                            // Participe passé
-                            Some(lexique::InfoverTemps::Pas) => true,
-                            _ => false,
-                        },
-                        _ => false,
-                    }
-                }),
-                _ => false,
-            }
+        .collect();
+
+    // Challenge records grouped by normalized word
+    let mut challenge_records_by_word: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+    for record in challenge_records.iter() {
+        challenge_records_by_word
+            .entry(&record.word)
+            .or_default()
+            .push(record);
+    }
+
+    // Challenge list: sorted normalized words, without duplicates
+    let challenges: Vec<String> = challenge_records_by_word
+        .keys()
+        .map(|word| word.to_string())
+        .collect();
+
+    // Difficulty of the challenges, in the same order as the challenge list
+    let features: Vec<Features> = challenge_records_by_word
+        .values()
+        .map(|records| Features::from_entries(records.iter().copied()))
+        .collect();
+    let scores = difficulty_scores(&features, &config.difficulty_weights);
+    let tiers = difficulty_tiers(&scores, config.difficulty_thresholds);
+
+    // Candidates for daily challenges, with the lemmas and the difficulty of
+    // each word
+    let candidates: Vec<Candidate> = challenge_records_by_word
+        .iter()
+        .zip(tiers.iter())
+        .map(|((word, records), difficulty)| Candidate {
+            word: word.to_string(),
+            lemmas: sort_dedup(
+                records
+                    .iter()
+                    .filter_map(|record| {
+                        let lemme = record.lemme.as_ref().unwrap_or(&record.ortho);
+                        transliterator.transliterate(lemme).ok()
+                    })
+                    .collect(),
+            ),
+            difficulty: Some(*difficulty),
         })
-        .map(|record| record.word.to_owned())
         .collect();
 
-    // Sort and remove duplicates
-    let challenges: Vec<String> = sort_dedup(challenges);
+    // Writer of the precompressed siblings of the generated files
+    let mut precompressor = Precompressor::new(&config.compression);
 
     if config.write_challenge {
-        let challenge_dir = config.output_path.join(config.challenge_dir);
+        let challenge_dir = config.output_path.join(&config.challenge_dir);
 
         create_dir_all(&challenge_dir)?;
 
+        // Display forms of the solutions (e.g. "élève" for "eleve")
+        let display_forms = display::display_forms(&database);
+
+        // Challenge data, given the solution
+        let challenge_data = |word: &str| {
+            let display = display_forms.get(word).map_or(word, String::as_str);
+            let records = challenge_records_by_word.get(word).into_iter().flatten();
+            let index = challenges.binary_search_by(|w| w.as_str().cmp(word)).ok();
+            Challenge {
+                difficulty: index.map(|index| tiers[index]),
+                score: index.map(|index| scores[index]),
+                ..Challenge::new(word, display, records.copied())
+            }
+        };
+
         // Write the total number of challenges
         let challenge_count = challenges.len();
-        std::fs::write(
-            config.output_path.join("challenge-count.txt"),
-            format!("{challenge_count}"),
-        )?;
+        let path = config.output_path.join("challenge-count.txt");
+        std::fs::write(&path, format!("{challenge_count}"))?;
+        precompressor.compress("challenge", path)?;
+
+        // Write the indices of the challenges of each difficulty tier
+        for difficulty in Difficulty::ALL {
+            let indices: String = tiers
+                .iter()
+                .enumerate()
+                .filter(|(_, tier)| **tier == difficulty)
+                .map(|(index, _)| format!("{}\n", index + 1))
+                .collect();
+            let path = config
+                .output_path
+                .join(format!("challenge-{difficulty}.txt"));
+            std::fs::write(&path, indices)?;
+            precompressor.compress("challenge", path)?;
+        }
 
         // Create random challenges
         for (index, word) in challenges.iter().enumerate() {
             let index = index + 1;
-            let path = challenge_dir.join(format!("{index}.txt"));
+            let path = challenge_dir.join(format!("{index}"));
 
-            std::fs::write(path, word)?;
+            let path = challenge_data(word).write(path, config.challenge_format)?;
+            precompressor.compress("challenge", path)?;
         }
 
         let today = chrono::Utc::now().date_naive();
 
-        // January 1, 1970
-        let unix_epoch = chrono::NaiveDateTime::from_timestamp_millis(0)
-            .unwrap()
-            .date();
+        // Dates of the daily challenges to write
+        let start_date = config.start_date.unwrap_or(today);
+        let end_date = match config.end_date {
+            Some(date) => Some(date),
+            None => start_date
+                .checked_add_days(chrono::Days::new(config.num_daily_challenges as u64))
+                .and_then(|date| date.pred_opt())
+                .filter(|_| config.num_daily_challenges > 0),
+        };
 
-        let days_since_unix_epoch =
-            chrono::NaiveDate::signed_duration_since(today, unix_epoch).num_days() as u64;
+        if start_date < config.launch_date {
+            let launch_date = config.launch_date;
+            return Err(CustomError(format!(
+                "start_date ({start_date}) must not be before launch_date ({launch_date})"
+            ))
+            .into());
+        }
 
-        // Previous daily challenges should not be altered by a new build.
-        // To preserve the sequence between builds, we use a counter-based RNG.
-        // We initialize the counter with the number of days since the UNIX epoch.
-        let mut rng = SquaresRng::new(days_since_unix_epoch, config.random_seed);
+        // Daily challenges that have already been published
+        let mut lock = match &config.lock_path {
+            // Published days would be drawn again without their lock
+            Some(path) if !path.exists() => {
+                return Err(CustomError(format!(
+                    "missing lock file {path:?}, create an empty file to start a new schedule"
+                ))
+                .into());
+            },
+            Some(path) => ScheduleLock::read(path)?,
+            None => ScheduleLock::default(),
+        };
 
-        let challenge_dates = today
-            .iter_days()
-            .take(num_daily_challenges)
-            .map(|date| date.format("%Y-%m-%d").to_string());
+        // Only published dates are locked, the next ones are drawn again by
+        // each build
+        let published_through = config.published_through.unwrap_or(today);
+
+        let constraints = Constraints {
+            repeat_interval: config.daily_repeat_interval,
+            lemma_interval: config.daily_lemma_interval,
+            distinct_first_letter: config.daily_distinct_first_letter,
+        };
+
+        let mut scheduler = Scheduler::new(&candidates, constraints);
+
+        // The schedule always starts at the launch date, so that the previous
+        // days constrain a date in the same way whatever the output window
+        let days = match end_date {
+            Some(end_date) => scheduler
+                .schedule(
+                    config.launch_date,
+                    start_date..=end_date,
+                    &lock,
+                    &config.daily_difficulty,
+                    config.random_seed,
+                )
+                .ok_or_else(|| {
+                    CustomError("no challenge to draw, the challenge list is empty".to_owned())
+                })?,
+            None => Vec::new(),
+        };
+
+        // Number of daily challenges easier than their target
+        let mut num_easier = 0;
 
         // Create daily challenges
-        for date in challenge_dates {
-            let path = challenge_dir.join(format!("{date}.txt"));
-            let word = challenges.choose(&mut rng).unwrap();
+        for Daily {
+            date,
+            word,
+            target,
+            draw,
+        } in days
+        {
+            match draw {
+                None => {
+                    if challenges
+                        .binary_search_by(|w| w.as_str().cmp(&word))
+                        .is_err()
+                    {
+                        eprintln!("Warning: locked word {word:?} on {date} is not a challenge");
+                    }
+                },
+                Some(draw) => {
+                    if !draw.satisfied {
+                        eprintln!("Warning: {word:?} on {date} does not satisfy the constraints");
+                    }
+                    if let (Some(target), Some(difficulty)) = (target, draw.difficulty) {
+                        if difficulty != target {
+                            eprintln!(
+                                "Warning: {word:?} on {date} is {difficulty} instead of {target}"
+                            );
+                            if difficulty < target {
+                                num_easier += 1;
+                            }
+                        }
+                    }
+                },
+            }
+
+            let path = challenge_dir.join(date.format("%Y-%m-%d").to_string());
+
+            let path = challenge_data(&word).write(path, config.challenge_format)?;
+            precompressor.compress("challenge", path)?;
+
+            if date <= published_through {
+                lock.insert(date, word);
+            }
+        }
+
+        if num_easier > 0 {
+            eprintln!("Warning: {num_easier} daily challenges are easier than their target");
+        }
 
-            std::fs::write(path, word)?;
+        if let Some(path) = &config.lock_path {
+            lock.write(path)?;
         }
     }
 
     if config.write_dictionary {
-        let dictionary_dir = config.output_path.join(config.dictionary_dir);
+        let dictionary_dir = config.output_path.join(&config.dictionary_dir);
+
+        // Split all words of the dictionary, whatever their first letter
+        let words: Vec<&str> = dictionary.iter().map(String::as_str).collect();
+        let sub_dictionaries = partition(&words, config.partition, config.partition_size);
 
-        create_dir_all(&dictionary_dir)?;
+        // Candidate encoders of each sub-dictionary
+        let candidates: Vec<&dyn Encoder> = match encoder {
+            Some(encoder) => vec![encoder],
+            None => encoders
+                .iter()
+                .filter(|encoder| encoder.is_exact())
+                .collect(),
+        };
 
-        // Get all first letters from challenges
-        let first_letters: HashSet<char> = challenges
+        // Train the encoders on all sub-dictionaries
+        let dictionaries: Vec<Vec<&str>> = sub_dictionaries
+            .iter()
+            .map(|(_, words)| words.clone())
+            .collect();
+        let trained: Vec<_> = candidates
             .iter()
-            .filter_map(|word| word.chars().nth(0))
+            .map(|encoder| encoder.train(&dictionaries))
+            .collect();
+        let candidates: Vec<(&dyn Encoder, Option<&Vec<u8>>)> = candidates
+            .iter()
+            .zip(trained.iter())
+            .map(|(encoder, trained)| match trained {
+                Some((trained, table)) => (trained.as_ref(), Some(table)),
+                None => (*encoder, None),
+            })
             .collect();
 
-        // Create sub-dictionaries
-        for word_length in min_length..=max_length {
-            for first_letter in &first_letters {
-                let sub_dictionary: Vec<_> = dictionary
-                    .iter()
-                    .filter(|word| word.chars().count() == word_length)
-                    .filter(|word| word.starts_with(first_letter.to_owned()))
-                    .map(|word| &word[1..])
-                    .collect();
+        let mut partitions = Vec::new();
+        let mut encoded = Vec::new();
+        let mut used = vec![false; candidates.len()];
 
-                if sub_dictionary.is_empty() {
-                    continue;
+        for (mut partition, sub_dictionary) in sub_dictionaries {
+            // Keep the smallest encoding, the first candidate on ties
+            let mut best: Option<(usize, usize, Vec<u8>)> = None;
+
+            for (i, (encoder, _)) in candidates.iter().enumerate() {
+                let mut content = Vec::new();
+                match encoder.encode(&mut content, &sub_dictionary) {
+                    Ok(()) => {},
+                    // Some encoders do not accept every sub-dictionary
+                    Err(_) if auto => continue,
+                    Err(err) => return Err(err.into()),
                 }
 
-                let path = dictionary_dir.join(format!("{word_length}{first_letter}.txt"));
-
-                match config.encoder_name.as_str() {
-                    "lines" => encoder::write_lines(path, sub_dictionary)?,
-                    "front" => encoder::write_front_coding(path, sub_dictionary)?,
-                    "frontopt" => encoder::write_front_coding_opt(path, sub_dictionary)?,
-                    "trie" => encoder::write_trie(path, sub_dictionary)?,
-                    _ => {
-                        let encoder = config.encoder_name;
-                        return Err(
-                            CustomError(format!("unknown encoding method {encoder:?}")).into()
-                        );
-                    },
+                let size = match config.auto_compression {
+                    Some(compression) if auto => compress::compress(&content, compression)?.len(),
+                    _ => content.len(),
                 };
+
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_size, ..)| size < *best_size)
+                {
+                    best = Some((size, i, content));
+                }
+            }
+
+            let Some((_, i, content)) = best else {
+                return Err(CustomError(format!("no encoder for {}", partition.file)).into());
+            };
+
+            let encoder = candidates[i].0;
+            partition.file = format!("{}.{}", partition.file, encoder.extension());
+            if auto {
+                partition.encoder = Some(encoder.name().to_owned());
+            }
+
+            used[i] = true;
+            partitions.push(partition);
+            encoded.push(content);
+        }
+
+        // Tables of the trained encoders that were used
+        let mut tables = Vec::new();
+
+        for ((encoder, table), used) in candidates.iter().zip(used) {
+            if let (Some(table), true) = (table, used) {
+                let file = if auto {
+                    format!("table-{}.{}", encoder.name(), encoder.extension())
+                } else {
+                    format!("table.{}", encoder.extension())
+                };
+                tables.push((encoder.name().to_owned(), file, table.as_slice()));
+            }
+        }
+
+        let mut index = PartitionIndex {
+            encoder: match encoder {
+                Some(encoder) => encoder.name().to_owned(),
+                None => AUTO_ENCODER.to_owned(),
+            },
+            tables: tables
+                .iter()
+                .map(|(name, file, _)| (name.clone(), file.clone()))
+                .collect(),
+            zstd_dictionary: None,
+            scheme: config.partition,
+            partitions,
+        };
+
+        let encoded: Vec<(&str, &[u8])> = index
+            .partitions
+            .iter()
+            .zip(encoded.iter())
+            .map(|(partition, content)| (partition.file.as_str(), content.as_slice()))
+            .collect();
+
+        if config.pack_dictionary {
+            let index_content = index.to_vec()?;
+
+            // Write a single pack, which holds the index and the table too
+            let mut files = encoded;
+            files.push((PARTITION_INDEX, &index_content));
+            for (_, file, table) in tables.iter() {
+                files.push((file, table));
+            }
+
+            let path = dictionary_dir.with_extension("pack");
+            if let Some(parent) = path.parent() {
+                create_dir_all(parent)?;
+            }
+            let mut writer = BufWriter::new(File::create(&path)?);
+            write_pack(&mut writer, &index.encoder, &files)?;
+            writer.flush()?;
+            drop(writer);
+
+            precompressor.compress("dictionary pack", path)?;
+        } else {
+            create_dir_all(&dictionary_dir)?;
+
+            // Write the tables of the encoders once
+            for (_, file, table) in tables.iter() {
+                let path = dictionary_dir.join(file);
+                std::fs::write(&path, table)?;
+                precompressor.compress("dictionary table", path)?;
+            }
+
+            let mut paths = Vec::new();
+
+            for (file, content) in encoded.iter() {
+                let path = dictionary_dir.join(file);
+                std::fs::write(&path, content)?;
+                paths.push(path);
+            }
+
+            if precompressor.train_zstd(&paths, &dictionary_dir, config.zstd_dictionary_size)? {
+                index.zstd_dictionary = Some(ZSTD_DICTIONARY.to_owned());
+            }
+
+            let path = dictionary_dir.join(PARTITION_INDEX);
+            std::fs::write(&path, index.to_vec()?)?;
+            precompressor.compress("dictionary index", path)?;
+
+            for path in paths {
+                precompressor.compress("dictionary", path)?;
             }
         }
     }
 
+    // Report the sizes of the generated files
+    print!("{precompressor}");
+
     Ok(())
 }
 
-/// Convert a word to lowercase and remove diacritics.
-fn normalize<S>(s: S) -> String
+/// Expand the dictionaries of an output directory into plain word lists.
+///
+/// Each sub-dictionary of the partition index is decoded with the encoder
+/// recorded in the index, and written to the destination directory with the
+/// same name and the `.txt` extension (e.g. `6a.txt`), one word per line.
+pub fn decode<P>(config: &Config, destination: P) -> anyhow::Result<()>
 where
-    S: AsRef<str>,
+    P: AsRef<Path>,
 {
-    let s = s.as_ref();
-    s.to_lowercase()
-        .chars()
-        .fold(String::with_capacity(s.len()), |mut result, c| {
-            match c {
-                'á' | 'à' | 'â' | 'ä' | 'ã' => result.push('a'),
-                'é' | 'è' | 'ê' | 'ë' => result.push('e'),
-                'î' | 'ï' => result.push('i'),
-                'ô' | 'ö' => result.push('o'),
-                'ú' | 'ù' | 'û' | 'ü' => result.push('u'),
-                'æ' => result.push_str("ae"),
-                'œ' => result.push_str("oe"),
-                'ç' => result.push('c'),
-                'ñ' => result.push('n'),
-                _ => {
-                    if !c.is_ascii() {
-                        panic!("unknown non-ascii character '{c}' in \"{s}\"")
-                    }
-                    result.push(c)
-                },
-            };
-            result
+    decode_with_encoders(config, destination, &default_encoders(config))
+}
+
+/// Expand the dictionaries of an output directory, with the dictionary
+/// encoders taken from a registry.
+///
+/// See [`decode`].
+pub fn decode_with_encoders<P>(
+    config: &Config,
+    destination: P,
+    encoders: &EncoderRegistry,
+) -> anyhow::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let dictionary_dir = config.output_path.join(&config.dictionary_dir);
+    let destination = destination.as_ref();
+
+    let index = read_index(&dictionary_dir)?;
+    let loaded = load_tables(encoders, &dictionary_dir, &index)?;
+
+    create_dir_all(destination)?;
+
+    for partition in index.partitions.iter() {
+        let encoder = partition_encoder(encoders, &loaded, &index, partition)?;
+        let path = dictionary_dir.join(&partition.file);
+        let content = std::fs::read(&path)?;
+
+        let words = encoder
+            .decode(&content, partition.suffix_length())
+            .map_err(|err| CustomError(format!("in {path:?}: {err}")))?;
+
+        let words: String = words
+            .into_iter()
+            .map(|word| format!("{}{word}\n", partition.prefix))
+            .collect();
+
+        std::fs::write(
+            destination.join(Path::new(&partition.file).with_extension("txt")),
+            words,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Measure the false positive rate of the dictionaries of an output directory.
+///
+/// For each sub-dictionary, `num_samples` random strings of lowercase ASCII
+/// letters with the same length and prefix are looked up, and those reported
+/// as accepted are counted. Random strings are very unlikely to be actual
+/// words, so exact encoders should report no false positives.
+pub fn check(config: &Config, num_samples: usize) -> anyhow::Result<()> {
+    check_with_encoders(config, num_samples, &default_encoders(config))
+}
+
+/// Measure the false positive rate of the dictionaries of an output
+/// directory, with the dictionary encoders taken from a registry.
+///
+/// See [`check`].
+pub fn check_with_encoders(
+    config: &Config,
+    num_samples: usize,
+    encoders: &EncoderRegistry,
+) -> anyhow::Result<()> {
+    let dictionary_dir = config.output_path.join(&config.dictionary_dir);
+
+    let index = read_index(&dictionary_dir)?;
+    let loaded = load_tables(encoders, &dictionary_dir, &index)?;
+
+    let mut rng = SquaresRng::new(0, config.random_seed);
+    let mut total_positives = 0;
+    let mut total_samples = 0;
+
+    for partition in index.partitions.iter() {
+        let encoder = partition_encoder(encoders, &loaded, &index, partition)?;
+        let path = dictionary_dir.join(&partition.file);
+        let content = std::fs::read(&path)?;
+        let length = partition.suffix_length();
+        let mut positives = 0;
+
+        for _ in 0..num_samples {
+            let word: String = (0..length).map(|_| rng.gen_range('a'..='z')).collect();
+            if encoder
+                .contains(&content, length, &word)
+                .map_err(|err| CustomError(format!("in {path:?}: {err}")))?
+            {
+                positives += 1;
+            }
+        }
+
+        let name = Path::new(&partition.file).with_extension("");
+        let rate = positives as f64 / num_samples.max(1) as f64;
+        println!(
+            "{}: {positives}/{num_samples} false positives ({rate:.4})",
+            name.display()
+        );
+
+        total_positives += positives;
+        total_samples += num_samples;
+    }
+
+    let rate = total_positives as f64 / total_samples.max(1) as f64;
+    println!("Total: {total_positives}/{total_samples} false positives ({rate:.4})");
+
+    Ok(())
+}
+
+/// Measure the sizes and times of the encoders on the dictionary of a
+/// configuration.
+///
+/// The dictionary is read and split into sub-dictionaries like [`build`] does,
+/// then each sub-dictionary is encoded and decoded `repeat` times by each
+/// encoder. Nothing is written to the output directory.
+pub fn bench(config: &Config, repeat: usize) -> anyhow::Result<BenchReport> {
+    bench_with_encoders(config, repeat, &default_encoders(config))
+}
+
+/// Measure the sizes and times of the encoders of a registry on the dictionary
+/// of a configuration.
+///
+/// See [`bench`].
+pub fn bench_with_encoders(
+    config: &Config,
+    repeat: usize,
+    encoders: &EncoderRegistry,
+) -> anyhow::Result<BenchReport> {
+    config.validate()?;
+
+    let transliterator = Transliterator::new(config.transliteration.clone(), config.ligatures);
+    let database = read_database(config, &transliterator)?;
+
+    let dictionary: Vec<String> =
+        sort_dedup(database.into_iter().map(|entry| entry.word).collect());
+    let words: Vec<&str> = dictionary.iter().map(String::as_str).collect();
+    let sub_dictionaries = partition(&words, config.partition, config.partition_size);
+
+    let report =
+        bench::bench_encoders(encoders.iter(), &sub_dictionaries, config.partition, repeat)?;
+
+    Ok(report)
+}
+
+/// Explain why a word is accepted or rejected by the build.
+///
+/// The word is normalized like the words of the sources, then every entry of
+/// the sources with the same normalized word is checked against each filter of
+/// the dictionary and of the challenges. If the word is in the dictionary, the
+/// sub-dictionary file that contains it is given. Nothing is written to the
+/// output directory.
+pub fn explain(config: &Config, word: &str) -> anyhow::Result<Explanation> {
+    explain_with_encoders(config, word, &default_encoders(config))
+}
+
+/// Explain why a word is accepted or rejected by the build, with the encoders
+/// of a registry.
+///
+/// See [`explain`].
+pub fn explain_with_encoders(
+    config: &Config,
+    word: &str,
+    encoders: &EncoderRegistry,
+) -> anyhow::Result<Explanation> {
+    config.validate()?;
+
+    let transliterator = Transliterator::new(config.transliteration.clone(), config.ligatures);
+    let word = transliterator.transliterate(word)?;
+    let (entries, skipped_rows) = read_entries(config, &transliterator)?;
+
+    // Rows that cannot be parsed are matched by their original spelling, if
+    // it is known
+    let skipped_rows = skipped_rows
+        .iter()
+        .filter(|err| {
+            err.ortho()
+                .and_then(|ortho| transliterator.transliterate(ortho).ok())
+                .is_some_and(|ortho| ortho == word)
         })
+        .map(ToString::to_string)
+        .collect();
+
+    let explanations: Vec<EntryExplanation> = entries
+        .iter()
+        .filter(|entry| entry.word == word)
+        .map(|entry| EntryExplanation::new(config, entry))
+        .collect();
+
+    // Split the dictionary like the build does, since the sub-dictionaries of
+    // the balanced scheme depend on the other words
+    let file = if explanations.iter().any(EntryExplanation::in_dictionary) {
+        let dictionary: Vec<String> = sort_dedup(
+            entries
+                .into_iter()
+                .filter(|entry| {
+                    Filter::DICTIONARY
+                        .iter()
+                        .all(|filter| filter.accepts(config, entry))
+                })
+                .map(|entry| entry.word)
+                .collect(),
+        );
+        let words: Vec<&str> = dictionary.iter().map(String::as_str).collect();
+        let length = word.chars().count();
+
+        partition(&words, config.partition, config.partition_size)
+            .into_iter()
+            .map(|(partition, _)| partition)
+            .find(|partition| {
+                partition.length == length
+                    && partition.first.as_str() <= word.as_str()
+                    && word.as_str() <= partition.last.as_str()
+            })
+            .map(|partition| match encoders.get(&config.encoder_name) {
+                Some(encoder) => format!("{}.{}", partition.file, encoder.extension()),
+                // The extension of the `auto` encoder depends on the
+                // sub-dictionary
+                None => partition.file,
+            })
+    } else {
+        None
+    };
+
+    Ok(Explanation {
+        word,
+        entries: explanations,
+        skipped_rows,
+        file,
+    })
+}
+
+/// Pack the dictionaries of an output directory into a single file.
+///
+/// The files listed in the partition index of the dictionary directory (the
+/// sub-dictionaries and the table of a trained encoder), and the index itself,
+/// are written to `{dictionary_dir}.pack` in the output directory (e.g.
+/// `public/dictionary.pack`). See [`write_pack`] for the format.
+pub fn pack(config: &Config) -> anyhow::Result<()> {
+    let dictionary_dir = config.output_path.join(&config.dictionary_dir);
+    let index = read_index(&dictionary_dir)?;
+
+    let names = std::iter::once(PARTITION_INDEX)
+        .chain(index.tables.values().map(String::as_str))
+        .chain(
+            index
+                .partitions
+                .iter()
+                .map(|partition| partition.file.as_str()),
+        );
+
+    let mut contents = Vec::new();
+    for name in names {
+        contents.push((name, std::fs::read(dictionary_dir.join(name))?));
+    }
+
+    let files: Vec<(&str, &[u8])> = contents
+        .iter()
+        .map(|(name, content)| (*name, content.as_slice()))
+        .collect();
+
+    let mut writer = BufWriter::new(File::create(dictionary_dir.with_extension("pack"))?);
+    write_pack(&mut writer, &index.encoder, &files)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Unpack the dictionary pack of an output directory into the dictionary
+/// directory.
+pub fn unpack(config: &Config) -> anyhow::Result<()> {
+    let dictionary_dir = config.output_path.join(&config.dictionary_dir);
+    let path = dictionary_dir.with_extension("pack");
+
+    let mut reader = PackReader::new(BufReader::new(File::open(&path)?))
+        .map_err(|err| CustomError(format!("in {path:?}: {err}")))?;
+
+    create_dir_all(&dictionary_dir)?;
+
+    for entry in reader.entries().to_vec() {
+        // Files must stay in the dictionary directory
+        if entry.name.contains(['/', '\\']) || entry.name.starts_with('.') {
+            eprintln!("Warning: skipped file {:?} in {path:?}", entry.name);
+            continue;
+        }
+
+        let content = reader
+            .read_entry(&entry)
+            .map_err(|err| CustomError(format!("in {path:?}: {err}")))?;
+
+        std::fs::write(dictionary_dir.join(&entry.name), content)?;
+    }
+
+    Ok(())
+}
+
+/// Read the word sources of a configuration, and keep the entries allowed in
+/// the dictionary.
+fn read_database(config: &Config, transliterator: &Transliterator) -> anyhow::Result<Vec<Entry>> {
+    let (entries, _) = read_entries(config, transliterator)?;
+
+    // Pre-filter the entries according to their grammatical categories, their
+    // length, and remove compound words
+    let database = entries
+        .into_iter()
+        .filter(|entry| {
+            Filter::DICTIONARY
+                .iter()
+                .all(|filter| filter.accepts(config, entry))
+        })
+        .collect();
+
+    Ok(database)
+}
+
+/// Read the word sources of a configuration, and report the words and rows
+/// that were skipped.
+///
+/// The rows that cannot be parsed are also returned.
+fn read_entries(
+    config: &Config,
+    transliterator: &Transliterator,
+) -> anyhow::Result<(Vec<Entry>, Vec<ParseError>)> {
+    // Read the word sources (the database by default)
+    let sources: Vec<Box<dyn WordSource>> = if config.sources.is_empty() {
+        vec![Box::new(LexiqueSource::new(&config.database_path))]
+    } else {
+        config.sources.iter().map(SourceConfig::open).collect()
+    };
+
+    let (entries, skipped) = read_sources(
+        sources.iter().map(AsRef::as_ref),
+        config.merge,
+        transliterator,
+        config.strict,
+    )?;
+
+    // Words that cannot be normalized are skipped
+    for err in skipped.words.iter() {
+        eprintln!("Warning: skipped word: {err}");
+    }
+
+    // Rows that cannot be parsed are skipped, unless in strict mode
+    if !skipped.rows.is_empty() {
+        eprint!(
+            "Warning: skipped rows: {}",
+            skipped.row_summary(config.skipped_row_examples)
+        );
+    }
+
+    Ok((entries, skipped.rows))
+}
+
+/// Create the default registry, with the encoders configured.
+fn default_encoders(config: &Config) -> EncoderRegistry {
+    let mut encoders = EncoderRegistry::default();
+    encoders.register(BlockedFrontCodingEncoder::new(config.front_block_size));
+    encoders.register(BloomEncoder::new(config.false_positive_rate));
+    encoders
+}
+
+/// Read the partition index of a dictionary directory.
+fn read_index(dictionary_dir: &Path) -> anyhow::Result<PartitionIndex> {
+    let path = dictionary_dir.join(PARTITION_INDEX);
+
+    if !path.exists() {
+        return Err(CustomError(format!("missing partition index {path:?}")).into());
+    }
+
+    PartitionIndex::read(path)
+}
+
+/// Load the tables written by trained encoders in a dictionary directory, by
+/// encoder name.
+fn load_tables(
+    encoders: &EncoderRegistry,
+    dictionary_dir: &Path,
+    index: &PartitionIndex,
+) -> anyhow::Result<BTreeMap<String, Box<dyn Encoder>>> {
+    let mut loaded = BTreeMap::new();
+
+    for (name, file) in index.tables.iter() {
+        let encoder = find_encoder(encoders, name)?;
+        let path = dictionary_dir.join(file);
+        let table = std::fs::read(&path)?;
+        let trained = encoder
+            .load(&table)
+            .map_err(|err| CustomError(format!("in {path:?}: {err}")))?;
+        loaded.insert(name.clone(), trained);
+    }
+
+    Ok(loaded)
+}
+
+/// Get the encoder of a sub-dictionary, with its table loaded if any.
+fn partition_encoder<'a>(
+    encoders: &'a EncoderRegistry,
+    loaded: &'a BTreeMap<String, Box<dyn Encoder>>,
+    index: &PartitionIndex,
+    partition: &Partition,
+) -> anyhow::Result<&'a dyn Encoder> {
+    let name = index.encoder_name(partition);
+    match loaded.get(name) {
+        Some(encoder) => Ok(encoder.as_ref()),
+        None => find_encoder(encoders, name),
+    }
+}
+
+/// Find an encoder by name in a registry.
+fn find_encoder<'a>(encoders: &'a EncoderRegistry, name: &str) -> anyhow::Result<&'a dyn Encoder> {
+    encoders.get(name).ok_or_else(|| {
+        let names = encoders.names().join(", ");
+        CustomError(format!(
+            "unknown encoding method {name:?} (expected one of {names})"
+        ))
+        .into()
+    })
 }
 
 /// Sort a list and remove duplicates.
@@ -302,3 +938,183 @@ where
     v.dedup();
     v
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        path::{Path, PathBuf},
+    };
+
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    const HEADER: &str = "ortho\tphon\tlemme\tcgram\tgenre\tnombre\tfreqlemfilms2\tfreqlemlivres\t\
+        freqfilms2\tfreqlivres\tinfover\tnbhomogr\tnbhomoph\tislem\tnblettres\tnbphons\tcvcv\t\
+        p_cvcv\tvoisorth\tvoisphon\tpuorth\tpuphon\tsyll\tnbsyll\tcv-cv\torthrenv\tphonrenv\t\
+        orthosyll\tcgramortho\tdeflem\tdefobs\told20\tpld20\tmorphoder\tnbmorph";
+
+    const WORDS: [&str; 12] = [
+        "bateau", "cheval", "fenetre", "jardin", "maison", "montagne", "nuage", "oiseau",
+        "riviere", "soleil", "tableau", "voiture",
+    ];
+
+    /// Empty temporary directory of a test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("vocab_generator_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a database of frequent nouns.
+    fn write_database(path: &Path, words: &[&str]) {
+        let rows: Vec<String> = words
+            .iter()
+            .map(|ortho| {
+                format!(
+                    "{ortho}\tphon\t{ortho}\tNOM\tm\ts\t10\t10\t10\t10\t\t1\t1\t1\t7\t1\tC\tC\t1\t1\t\
+                     1\t1\t{ortho}\t1\t\t\t\t\tNOM\t\t\t1\t1\t{ortho}\t1"
+                )
+            })
+            .collect();
+        std::fs::write(path, format!("{HEADER}\n{}\n", rows.join("\n"))).unwrap();
+    }
+
+    /// Words of the daily challenges of an output directory, by date.
+    fn read_daily(config: &Config) -> BTreeMap<NaiveDate, String> {
+        std::fs::read_dir(config.output_path.join(&config.challenge_dir))
+            .unwrap()
+            .filter_map(|entry| {
+                let path = entry.unwrap().path();
+                let date = path.file_stem()?.to_str()?.parse().ok()?;
+                let content = std::fs::read_to_string(path).unwrap();
+                Some((date, content.lines().next()?.to_owned()))
+            })
+            .collect()
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn build_windows() {
+        let dir = test_dir("windows");
+        let config = |start: &str, end: &str| Config {
+            database_path: dir.join("database.tsv"),
+            output_path: dir.join(start),
+            write_dictionary: false,
+            start_date: Some(date(start)),
+            end_date: Some(date(end)),
+            ..Config::default()
+        };
+        write_database(&dir.join("database.tsv"), &WORDS);
+
+        // Windows starting on different days agree on the days they share
+        let first = config("2024-01-01", "2024-02-15");
+        let second = config("2024-01-20", "2024-03-01");
+        build(first.clone()).unwrap();
+        build(second.clone()).unwrap();
+        let first = read_daily(&first);
+        let second = read_daily(&second);
+
+        assert_eq!(first.len(), 46);
+        assert_eq!(second.len(), 42);
+        let shared: Vec<_> = first.range(date("2024-01-20")..).collect();
+        assert_eq!(shared.len(), 27);
+        assert_eq!(
+            shared,
+            second.range(..=date("2024-02-15")).collect::<Vec<_>>()
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn build_zstd() {
+        let dir = test_dir("zstd");
+        let mut config = Config {
+            database_path: dir.join("database.tsv"),
+            output_path: dir.join("output"),
+            write_challenge: false,
+            compression: vec![Compression::Zstd],
+            ..Config::default()
+        };
+
+        // Enough words to train a zstd dictionary
+        let syllables = ["ba", "ce", "di", "fo", "gu", "la", "me", "ni", "po", "ru"];
+        let words: Vec<String> = syllables
+            .iter()
+            .flat_map(|a| syllables.iter().map(move |b| format!("{a}{b}")))
+            .flat_map(|ab| syllables.iter().map(move |c| format!("{ab}{c}")))
+            .collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        write_database(&config.database_path, &words);
+
+        // The index lists the zstd dictionary
+        build(config.clone()).unwrap();
+        let path = config.output_path.join(&config.dictionary_dir);
+        let index = PartitionIndex::read(path.join(PARTITION_INDEX)).unwrap();
+        assert_eq!(index.zstd_dictionary.as_deref(), Some(ZSTD_DICTIONARY));
+        assert!(path.join(ZSTD_DICTIONARY).exists());
+
+        // The pack has a zstd sibling, even without a trained dictionary
+        config.pack_dictionary = true;
+        build(config.clone()).unwrap();
+        assert!(path.with_extension("pack").exists());
+        assert!(path.with_extension("pack.zst").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rebuild_with_lock() {
+        let dir = test_dir("lock");
+        let lock_path = dir.join("daily.lock");
+        let published_through = date("2024-01-05");
+        let config = Config {
+            database_path: dir.join("database.tsv"),
+            output_path: dir.join("output"),
+            write_dictionary: false,
+            start_date: Some(date("2024-01-01")),
+            end_date: Some(date("2024-01-20")),
+            lock_path: Some(lock_path.clone()),
+            published_through: Some(published_through),
+            ..Config::default()
+        };
+
+        // A new schedule starts from an empty lock file
+        write_database(&config.database_path, &WORDS);
+        assert!(build(config.clone()).is_err());
+        std::fs::write(&lock_path, "").unwrap();
+        build(config.clone()).unwrap();
+        let first = read_daily(&config);
+        assert_eq!(first.len(), 20);
+
+        // Only the published dates are locked
+        let lock = ScheduleLock::read(&lock_path).unwrap();
+        let locked: Vec<_> = lock.iter().map(|(date, _)| *date).collect();
+        assert_eq!(locked.last(), Some(&published_through));
+        assert_eq!(locked.len(), 5);
+
+        // Remove the word of the first date that is not published
+        let next_date = published_through.succ_opt().unwrap();
+        let removed = first[&next_date].as_str();
+        let words: Vec<&str> = WORDS.into_iter().filter(|word| *word != removed).collect();
+        write_database(&config.database_path, &words);
+        build(config.clone()).unwrap();
+        let second = read_daily(&config);
+
+        for (date, word) in first.iter() {
+            if *date <= published_through {
+                assert_eq!(second[date], *word);
+            }
+        }
+        assert_ne!(second[&next_date], removed);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
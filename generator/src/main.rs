@@ -1,21 +1,46 @@
 use std::path::PathBuf;
 
-use clap::Parser;
-use vocab_generator::{build, Config};
+use chrono::NaiveDate;
+use clap::{builder::PossibleValuesParser, Parser, Subcommand};
+use vocab_generator::{
+    bench, build, check, decode, explain, pack, unpack, ChallengeFormat, Compression, Config,
+    EncoderRegistry, PartitionScheme, AUTO_ENCODER,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to a TOML configuration file.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
     /// Path to the word database.
     #[arg(long)]
     database: Option<PathBuf>,
 
-    /// Dictionary encoding method (lines, front, frontopt, trie).
-    #[arg(long)]
+    /// Fail on the first row of the database that cannot be parsed.
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// Dictionary encoding method, or `auto` to pick the smallest encoding of
+    /// each sub-dictionary.
+    #[arg(long, global = true, value_parser = encoder_names())]
     encoder: Option<String>,
 
-    /// Output directory.
+    /// Compression applied before comparing the encodings of the auto
+    /// encoder.
+    #[arg(long)]
+    auto_compression: Option<Compression>,
+
+    /// False positive rate of the bloom encoder.
     #[arg(long)]
+    false_positive_rate: Option<f64>,
+
+    /// Output directory.
+    #[arg(long, global = true)]
     output: Option<PathBuf>,
 
     /// Directory for the challenges.
@@ -23,9 +48,18 @@ struct Args {
     challenge_dir: Option<PathBuf>,
 
     /// Directory for the dictionaries.
-    #[arg(long)]
+    #[arg(long, global = true)]
     dictionary_dir: Option<PathBuf>,
 
+    /// Challenge file format (text, json). The app reads the text format only.
+    #[arg(long)]
+    challenge_format: Option<ChallengeFormat>,
+
+    /// Write precompressed siblings of the generated files (gzip, brotli,
+    /// zstd), separated by commas.
+    #[arg(long, value_delimiter = ',')]
+    compress: Vec<Compression>,
+
     /// Do not output challenges.
     #[arg(long)]
     no_challenge: bool,
@@ -34,30 +68,188 @@ struct Args {
     #[arg(long)]
     no_dictionary: bool,
 
+    /// Method to split the dictionary into sub-dictionaries.
+    #[arg(long)]
+    partition: Option<PartitionScheme>,
+
+    /// Maximum number of words per sub-dictionary of the balanced
+    /// partitioning scheme.
+    #[arg(long)]
+    partition_size: Option<usize>,
+
+    /// Write the dictionaries into a single pack.
+    #[arg(long)]
+    pack_dictionary: bool,
+
     /// Specify a random seed.
     #[arg(long)]
     seed: Option<u64>,
+
+    /// Path to the lock file of published daily challenges.
+    #[arg(long)]
+    lock: Option<PathBuf>,
+
+    /// Last published daily challenge, recorded in the lock file (today by
+    /// default).
+    #[arg(long)]
+    published_through: Option<NaiveDate>,
+
+    /// First daily challenge to write (yyyy-mm-dd), today by default.
+    #[arg(long)]
+    start_date: Option<NaiveDate>,
+
+    /// Last daily challenge to write (yyyy-mm-dd).
+    #[arg(long)]
+    end_date: Option<NaiveDate>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Build the static files (default).
+    Build,
+
+    /// Expand the dictionaries of the output directory into plain word lists.
+    Decode {
+        /// Directory of the plain word lists.
+        destination: PathBuf,
+    },
+
+    /// Measure the false positive rate of the dictionaries of the output
+    /// directory against random strings.
+    Check {
+        /// Number of random strings per sub-dictionary.
+        #[arg(long, default_value_t = 10000)]
+        samples: usize,
+    },
+
+    /// Measure the size and speed of every encoder on the dictionary.
+    BenchEncoders {
+        /// Number of runs of which the times are averaged.
+        #[arg(long, default_value_t = 10)]
+        repeat: usize,
+
+        /// Path to write the report in JSON.
+        #[arg(long)]
+        json: Option<PathBuf>,
+    },
+
+    /// Explain why a word is accepted or rejected by the build.
+    Explain {
+        /// Word to explain, normalized like the words of the sources.
+        word: String,
+    },
+
+    /// Pack the dictionaries of the output directory into a single file.
+    Pack,
+
+    /// Unpack the dictionary pack of the output directory.
+    Unpack,
 }
 
 fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
-
-    let config = Config {
-        database_path: args.database.unwrap_or("Lexique383.tsv".into()),
-        encoder_name: args.encoder.unwrap_or("front".to_owned()),
-        output_path: args.output.unwrap_or("public".into()),
-        challenge_dir: args.challenge_dir.unwrap_or("challenge".into()),
-        dictionary_dir: args.dictionary_dir.unwrap_or("dictionary".into()),
-        write_challenge: !args.no_challenge,
-        write_dictionary: !args.no_dictionary,
-        random_seed: args.seed.unwrap_or(0x548c9decbce65297),
-    };
+    let mut args = Args::parse();
 
-    let result = build(config);
+    let command = args.command.take();
+    // Errors are printed to stderr, with a non-zero exit code
+    load_config(args).and_then(|config| match command {
+        None | Some(Command::Build) => build(config),
+        Some(Command::Decode { destination }) => decode(&config, destination),
+        Some(Command::Check { samples }) => check(&config, samples),
+        Some(Command::BenchEncoders { repeat, json }) => {
+            bench(&config, repeat).and_then(|report| {
+                print!("{report}");
+                if let Some(path) = json {
+                    std::fs::write(path, serde_json::to_vec_pretty(&report)?)?;
+                }
+                Ok(())
+            })
+        },
+        Some(Command::Explain { word }) => {
+            explain(&config, &word).map(|explanation| print!("{explanation}"))
+        },
+        Some(Command::Pack) => pack(&config),
+        Some(Command::Unpack) => unpack(&config),
+    })
+}
 
-    if let Some(err) = result.err() {
-        println!("Error: {err}");
+/// Names of the registered encoders, and `auto`.
+fn encoder_names() -> PossibleValuesParser {
+    let names: Vec<String> = EncoderRegistry::default()
+        .names()
+        .into_iter()
+        .chain([AUTO_ENCODER])
+        .map(str::to_owned)
+        .collect();
+    PossibleValuesParser::new(names)
+}
+
+/// Load the configuration file, if any, and apply command line arguments.
+fn load_config(args: Args) -> anyhow::Result<Config> {
+    let mut config = match args.config {
+        Some(path) => Config::from_file(path)?,
+        None => Config::default(),
+    };
+
+    if let Some(database) = args.database {
+        config.database_path = database;
+    }
+    if args.strict {
+        config.strict = true;
+    }
+    if let Some(encoder) = args.encoder {
+        config.encoder_name = encoder;
+    }
+    if let Some(auto_compression) = args.auto_compression {
+        config.auto_compression = Some(auto_compression);
+    }
+    if let Some(false_positive_rate) = args.false_positive_rate {
+        config.false_positive_rate = false_positive_rate;
+    }
+    if let Some(output) = args.output {
+        config.output_path = output;
+    }
+    if let Some(challenge_dir) = args.challenge_dir {
+        config.challenge_dir = challenge_dir;
+    }
+    if let Some(dictionary_dir) = args.dictionary_dir {
+        config.dictionary_dir = dictionary_dir;
+    }
+    if let Some(challenge_format) = args.challenge_format {
+        config.challenge_format = challenge_format;
+    }
+    if !args.compress.is_empty() {
+        config.compression = args.compress;
+    }
+    if args.no_challenge {
+        config.write_challenge = false;
+    }
+    if args.no_dictionary {
+        config.write_dictionary = false;
+    }
+    if let Some(partition) = args.partition {
+        config.partition = partition;
+    }
+    if let Some(partition_size) = args.partition_size {
+        config.partition_size = partition_size;
+    }
+    if args.pack_dictionary {
+        config.pack_dictionary = true;
+    }
+    if let Some(seed) = args.seed {
+        config.random_seed = seed;
+    }
+    if let Some(lock) = args.lock {
+        config.lock_path = Some(lock);
+    }
+    if let Some(published_through) = args.published_through {
+        config.published_through = Some(published_through);
+    }
+    if let Some(start_date) = args.start_date {
+        config.start_date = Some(start_date);
+    }
+    if let Some(end_date) = args.end_date {
+        config.end_date = Some(end_date);
     }
 
-    Ok(())
+    Ok(config)
 }
@@ -0,0 +1,249 @@
+use std::io::{Read, Seek, SeekFrom, Write};
+
+/// Magic number at the start of a pack.
+const MAGIC: &[u8; 8] = b"VOCABPAK";
+
+/// Entry of the index of a pack.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PackEntry {
+    /// Name of the file in the dictionary directory (e.g. `6a.txt`).
+    pub name: String,
+    /// Position of the file from the start of the pack.
+    pub offset: u64,
+    /// Size of the file, in bytes.
+    pub size: u64,
+}
+
+/// Write the files of a dictionary directory into a single pack.
+///
+/// A pack starts with a header, made of (integers in little endian):
+///
+/// - the magic number `VOCABPAK`,
+/// - the size of the header (`u32`), so that clients can fetch it at once,
+/// - the length (`u8`) and the name of the encoder,
+/// - the number of entries (`u32`),
+/// - for each entry, sorted by name: the length (`u8`) and the name of the file
+///   (e.g. `6a.txt`, `index.json`), its offset (`u32`) and its size (`u32`).
+///
+/// The files follow, in the order of the entries.
+pub fn write_pack<W>(
+    writer: &mut W,
+    encoder_name: &str,
+    files: &[(&str, &[u8])],
+) -> std::io::Result<()>
+where
+    W: Write + ?Sized,
+{
+    let mut files = files.to_vec();
+    files.sort_by_key(|(name, _)| *name);
+
+    let name = encoder_name.as_bytes();
+    let header_size = MAGIC.len()
+        + 4
+        + 1
+        + name.len()
+        + 4
+        + files
+            .iter()
+            .map(|(name, _)| 1 + name.len() + 8)
+            .sum::<usize>();
+
+    let invalid = |message: &str| std::io::Error::new(std::io::ErrorKind::InvalidInput, message);
+
+    let name_length = u8::try_from(name.len()).map_err(|_| invalid("encoder name too long"))?;
+
+    let mut header = Vec::with_capacity(header_size);
+    header.extend(MAGIC);
+    header.extend(to_u32(header_size)?.to_le_bytes());
+    header.push(name_length);
+    header.extend(name);
+    header.extend(to_u32(files.len())?.to_le_bytes());
+
+    let mut offset = header_size;
+
+    for (name, content) in files.iter() {
+        let name_length = u8::try_from(name.len()).map_err(|_| invalid("file name too long"))?;
+        header.push(name_length);
+        header.extend(name.as_bytes());
+        header.extend(to_u32(offset)?.to_le_bytes());
+        header.extend(to_u32(content.len())?.to_le_bytes());
+        offset += content.len();
+    }
+
+    writer.write_all(&header)?;
+
+    for (_, content) in files.iter() {
+        writer.write_all(content)?;
+    }
+
+    Ok(())
+}
+
+/// Reader of a pack written by [`write_pack`].
+#[derive(Debug)]
+pub struct PackReader<R> {
+    reader: R,
+    encoder_name: String,
+    entries: Vec<PackEntry>,
+}
+
+impl<R> PackReader<R>
+where
+    R: Read + Seek,
+{
+    /// Read the header of a pack.
+    pub fn new(mut reader: R) -> std::io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a dictionary pack"));
+        }
+
+        let header_size = read_u32(&mut reader)? as u64;
+
+        let encoder_name = read_name(&mut reader)
+            .map_err(|err| invalid_data(&format!("invalid encoder name: {err}")))?;
+
+        let num_entries = read_u32(&mut reader)?;
+        let mut entries = Vec::new();
+
+        for _ in 0..num_entries {
+            let name = read_name(&mut reader)
+                .map_err(|err| invalid_data(&format!("invalid file name: {err}")))?;
+            let offset = read_u32(&mut reader)? as u64;
+            let size = read_u32(&mut reader)? as u64;
+
+            if offset < header_size {
+                return Err(invalid_data("entry inside the header"));
+            }
+
+            entries.push(PackEntry { name, offset, size });
+        }
+
+        Ok(Self {
+            reader,
+            encoder_name,
+            entries,
+        })
+    }
+
+    /// Name of the encoder of the sub-dictionaries.
+    pub fn encoder_name(&self) -> &str {
+        &self.encoder_name
+    }
+
+    /// Entries of the index, sorted by name.
+    pub fn entries(&self) -> &[PackEntry] {
+        &self.entries
+    }
+
+    /// Read a file given its name, if any.
+    pub fn read(&mut self, name: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let Some(entry) = self
+            .entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .cloned()
+        else {
+            return Ok(None);
+        };
+
+        self.read_entry(&entry).map(Some)
+    }
+
+    /// Read the file of an entry.
+    pub fn read_entry(&mut self, entry: &PackEntry) -> std::io::Result<Vec<u8>> {
+        self.reader.seek(SeekFrom::Start(entry.offset))?;
+        let mut content = Vec::new();
+        (&mut self.reader)
+            .take(entry.size)
+            .read_to_end(&mut content)?;
+
+        if content.len() as u64 != entry.size {
+            return Err(invalid_data("truncated file"));
+        }
+
+        Ok(content)
+    }
+}
+
+fn to_u32(value: usize) -> std::io::Result<u32> {
+    u32::try_from(value).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "pack larger than 4 GiB")
+    })
+}
+
+fn read_u32<R>(reader: &mut R) -> std::io::Result<u32>
+where
+    R: Read,
+{
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Read a name prefixed with its length (`u8`).
+fn read_name<R>(reader: &mut R) -> std::io::Result<String>
+where
+    R: Read,
+{
+    let mut length = [0; 1];
+    reader.read_exact(&mut length)?;
+    let mut name = vec![0; length[0] as usize];
+    reader.read_exact(&mut name)?;
+    String::from_utf8(name).map_err(|_| invalid_data("not UTF-8"))
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let files: [(&str, &[u8]); 3] = [
+            ("7b.txt", b"ateau\nbines"),
+            ("6\u{e9}.txt", b"l\xc3\xa8ve"),
+            ("index.json", b"{}"),
+        ];
+
+        let mut pack = Vec::new();
+        write_pack(&mut pack, "front", &files).unwrap();
+
+        let mut reader = PackReader::new(Cursor::new(pack)).unwrap();
+        assert_eq!(reader.encoder_name(), "front");
+
+        let names: Vec<_> = reader
+            .entries()
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(names, ["6\u{e9}.txt", "7b.txt", "index.json"]);
+
+        // The header takes 8 + 4 + 1 + 5 + 4 + (1 + 7 + 8) + (1 + 6 + 8)
+        // + (1 + 10 + 8) bytes
+        assert_eq!(reader.entries()[0].offset, 72);
+
+        for (name, content) in files {
+            assert_eq!(reader.read(name).unwrap().unwrap(), content);
+        }
+        assert_eq!(reader.read("7a.txt").unwrap(), None);
+    }
+
+    #[test]
+    fn read_invalid() {
+        assert!(PackReader::new(Cursor::new(b"VOCABPIK")).is_err());
+
+        let mut pack = Vec::new();
+        write_pack(&mut pack, "front", &[("6a.txt", b"bcdef")]).unwrap();
+        pack.pop();
+
+        let mut reader = PackReader::new(Cursor::new(pack)).unwrap();
+        assert!(reader.read("6a.txt").is_err());
+    }
+}
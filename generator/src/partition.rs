@@ -0,0 +1,270 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+/// Name of the partition index file, in the dictionary directory.
+pub const PARTITION_INDEX: &str = "index.json";
+
+/// Method to split the dictionary into sub-dictionaries.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum PartitionScheme {
+    /// One sub-dictionary per word length (e.g. `6`).
+    Length,
+    /// One sub-dictionary per word length and first letter (e.g. `6a`).
+    #[default]
+    LengthLetter,
+    /// One sub-dictionary per word length and first two letters (e.g. `6ab`).
+    LengthTwoLetters,
+    /// Sub-dictionaries of consecutive words of the same length, with at most
+    /// `partition_size` words each (e.g. `6-1`, `6-2`).
+    Balanced,
+}
+
+/// Sub-dictionary of the partition index.
+///
+/// A word belongs to the sub-dictionary if it has the given length, starts
+/// with the prefix, and is between the first and last words.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Partition {
+    /// Name of the file in the dictionary directory (e.g. `6a.txt`).
+    pub file: String,
+    /// Name of the encoder of the file, if it differs from the encoder of the
+    /// index (with the `auto` encoder).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoder: Option<String>,
+    /// Length of the words.
+    pub length: usize,
+    /// Prefix of all the words, which is not encoded.
+    pub prefix: String,
+    /// First word.
+    pub first: String,
+    /// Last word.
+    pub last: String,
+    /// Number of words.
+    pub count: usize,
+}
+
+impl Partition {
+    /// Length of the encoded words, without the prefix.
+    pub fn suffix_length(&self) -> usize {
+        self.length - self.prefix.chars().count()
+    }
+}
+
+/// Index of the sub-dictionaries, written as JSON next to them.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PartitionIndex {
+    /// Name of the encoder of the sub-dictionaries (or `auto`).
+    pub encoder: String,
+    /// Names of the files of the tables of trained encoders, by encoder.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tables: BTreeMap<String, String>,
+    /// Name of the file of the zstd dictionary of the `.zst` siblings, if
+    /// any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zstd_dictionary: Option<String>,
+    /// Partitioning scheme.
+    pub scheme: PartitionScheme,
+    /// Sub-dictionaries, sorted by length then first word.
+    pub partitions: Vec<Partition>,
+}
+
+impl PartitionIndex {
+    /// Name of the encoder of a sub-dictionary.
+    pub fn encoder_name<'a>(&'a self, partition: &'a Partition) -> &'a str {
+        partition.encoder.as_deref().unwrap_or(&self.encoder)
+    }
+
+    /// Read an index file.
+    pub fn read<P>(path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let content = std::fs::read(path)?;
+        Self::from_slice(&content).map_err(|err| anyhow::anyhow!("in {path:?}: {err}"))
+    }
+
+    /// Parse an index.
+    pub fn from_slice(content: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(content)
+    }
+
+    /// Serialize the index.
+    pub fn to_vec(&self) -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec(self)
+    }
+}
+
+/// Split sorted words into sub-dictionaries, whose words have their prefix
+/// removed.
+///
+/// File names are given without extension, which depends on the encoder.
+/// `size` is the maximum number of words of the `balanced` scheme.
+pub fn partition<'a>(
+    words: &[&'a str],
+    scheme: PartitionScheme,
+    size: usize,
+) -> Vec<(Partition, Vec<&'a str>)> {
+    // Group the words by length, then by prefix
+    let mut groups: Vec<(usize, &str, Vec<&'a str>)> = Vec::new();
+
+    let mut words = words.to_vec();
+    words.sort_by_key(|word| word.chars().count());
+
+    for word in words {
+        let length = word.chars().count();
+
+        let prefix_length = match scheme {
+            PartitionScheme::Length | PartitionScheme::Balanced => 0,
+            PartitionScheme::LengthLetter => 1,
+            PartitionScheme::LengthTwoLetters => 2,
+        };
+        let prefix_end = word
+            .char_indices()
+            .nth(prefix_length)
+            .map_or(word.len(), |(index, _)| index);
+        let prefix = &word[..prefix_end];
+
+        match groups.last_mut() {
+            Some((l, p, group)) if *l == length && *p == prefix => group.push(word),
+            _ => groups.push((length, prefix, vec![word])),
+        }
+    }
+
+    let mut partitions = Vec::new();
+
+    for (length, prefix, group) in groups {
+        let chunks: Vec<&[&str]> = match scheme {
+            PartitionScheme::Balanced => {
+                // Balance the sizes of the chunks
+                let num_chunks = group.len().div_ceil(size.max(1));
+                let chunk_size = group.len().div_ceil(num_chunks);
+                group.chunks(chunk_size).collect()
+            },
+            _ => vec![&group],
+        };
+
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let (Some(first), Some(last)) = (chunk.first(), chunk.last()) else {
+                continue;
+            };
+
+            let (name, prefix) = match scheme {
+                PartitionScheme::Balanced => {
+                    // Prefix shared by all words of the chunk
+                    let prefix_end = first
+                        .char_indices()
+                        .zip(last.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map_or(first.len(), |((index, _), _)| index);
+                    (format!("{length}-{}", index + 1), &first[..prefix_end])
+                },
+                _ => (format!("{length}{prefix}"), prefix),
+            };
+
+            let suffixes = chunk.iter().map(|word| &word[prefix.len()..]).collect();
+
+            partitions.push((
+                Partition {
+                    file: name,
+                    encoder: None,
+                    length,
+                    prefix: prefix.to_owned(),
+                    first: first.to_string(),
+                    last: last.to_string(),
+                    count: chunk.len(),
+                },
+                suffixes,
+            ));
+        }
+    }
+
+    partitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 7] = ["abc", "abd", "acd", "bcd", "ab", "ba", "bb"];
+
+    fn names(scheme: PartitionScheme, size: usize) -> Vec<(String, String, Vec<&'static str>)> {
+        let mut words = WORDS.to_vec();
+        words.sort();
+        partition(&words, scheme, size)
+            .into_iter()
+            .map(|(partition, words)| (partition.file, partition.prefix, words))
+            .collect()
+    }
+
+    #[test]
+    fn schemes() {
+        let s = |s: &str| s.to_owned();
+
+        assert_eq!(
+            names(PartitionScheme::Length, 0),
+            [
+                (s("2"), s(""), vec!["ab", "ba", "bb"]),
+                (s("3"), s(""), vec!["abc", "abd", "acd", "bcd"]),
+            ]
+        );
+        assert_eq!(
+            names(PartitionScheme::LengthLetter, 0),
+            [
+                (s("2a"), s("a"), vec!["b"]),
+                (s("2b"), s("b"), vec!["a", "b"]),
+                (s("3a"), s("a"), vec!["bc", "bd", "cd"]),
+                (s("3b"), s("b"), vec!["cd"]),
+            ]
+        );
+        assert_eq!(
+            names(PartitionScheme::LengthTwoLetters, 0),
+            [
+                (s("2ab"), s("ab"), vec![""]),
+                (s("2ba"), s("ba"), vec![""]),
+                (s("2bb"), s("bb"), vec![""]),
+                (s("3ab"), s("ab"), vec!["c", "d"]),
+                (s("3ac"), s("ac"), vec!["d"]),
+                (s("3bc"), s("bc"), vec!["d"]),
+            ]
+        );
+        // 4 words in chunks of at most 3 are split into 2 + 2
+        assert_eq!(
+            names(PartitionScheme::Balanced, 3),
+            [
+                (s("2-1"), s(""), vec!["ab", "ba", "bb"]),
+                (s("3-1"), s("ab"), vec!["c", "d"]),
+                (s("3-2"), s(""), vec!["acd", "bcd"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn index_json() {
+        let index = PartitionIndex {
+            encoder: "auto".to_owned(),
+            tables: BTreeMap::from([("huffman".to_owned(), "table-huffman.bin".to_owned())]),
+            zstd_dictionary: None,
+            scheme: PartitionScheme::LengthLetter,
+            partitions: vec![Partition {
+                file: "6a.txt".to_owned(),
+                encoder: Some("front".to_owned()),
+                length: 6,
+                prefix: "a".to_owned(),
+                first: "abaque".to_owned(),
+                last: "azimut".to_owned(),
+                count: 2,
+            }],
+        };
+
+        let json = index.to_vec().unwrap();
+        assert_eq!(
+            String::from_utf8(json.clone()).unwrap(),
+            r#"{"encoder":"auto","tables":{"huffman":"table-huffman.bin"},"scheme":"length-letter","partitions":[{"file":"6a.txt","encoder":"front","length":6,"prefix":"a","first":"abaque","last":"azimut","count":2}]}"#
+        );
+        assert_eq!(PartitionIndex::from_slice(&json).unwrap(), index);
+        assert_eq!(index.encoder_name(&index.partitions[0]), "front");
+    }
+}
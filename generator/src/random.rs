@@ -52,7 +52,8 @@ impl RngCore for SquaresRng {
 
     #[inline]
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
-        Ok(self.fill_bytes(dest))
+        self.fill_bytes(dest);
+        Ok(())
     }
 }
 
@@ -79,6 +80,7 @@ impl SeedableRng for SquaresRng {
 /// of John von Neumann's middle-square random number generator, presented in
 /// Bernard Widynski's paper [Squares: A Fast Counter-Based RNG](https://arxiv.org/abs/2004.06278).
 #[inline]
+#[allow(clippy::manual_rotate, clippy::needless_late_init)]
 pub const fn squares_32(counter: u64, key: u64) -> u32 {
     let mut x;
     let y;
@@ -118,6 +120,7 @@ pub const fn squares_32(counter: u64, key: u64) -> u32 {
 /// of John von Neumann's middle-square random number generator, presented in
 /// Bernard Widynski's paper [Squares: A Fast Counter-Based RNG](https://arxiv.org/abs/2004.06278).
 #[inline]
+#[allow(clippy::manual_rotate, clippy::needless_late_init)]
 pub const fn squares_64(counter: u64, key: u64) -> u64 {
     let mut x;
     let y;
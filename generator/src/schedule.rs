@@ -0,0 +1,708 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufWriter, Write},
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{difficulty::Difficulty, random::SquaresRng};
+
+/// Maximum number of random draws before scanning the candidates.
+const MAX_DRAWS: usize = 64;
+
+/// Constraints between daily challenges.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Constraints {
+    /// Minimum number of days between two occurrences of the same word.
+    pub repeat_interval: usize,
+    /// Minimum number of days between two words sharing a lemma.
+    pub lemma_interval: usize,
+    /// Whether consecutive days must have different first letters.
+    pub distinct_first_letter: bool,
+}
+
+/// Target difficulty of the daily challenges of each weekday.
+///
+/// Weekdays without target draw from all candidates.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifficultyCurve {
+    pub monday: Option<Difficulty>,
+    pub tuesday: Option<Difficulty>,
+    pub wednesday: Option<Difficulty>,
+    pub thursday: Option<Difficulty>,
+    pub friday: Option<Difficulty>,
+    pub saturday: Option<Difficulty>,
+    pub sunday: Option<Difficulty>,
+}
+
+impl DifficultyCurve {
+    /// Get the target difficulty of a weekday.
+    pub fn get(&self, weekday: Weekday) -> Option<Difficulty> {
+        match weekday {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+}
+
+/// Daily challenge candidate.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Candidate {
+    /// Normalized word.
+    pub word: String,
+    /// Normalized lemmas of the word.
+    pub lemmas: Vec<String>,
+    /// Difficulty tier of the word, if known.
+    pub difficulty: Option<Difficulty>,
+}
+
+/// Word drawn by the scheduler.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Draw<'a> {
+    /// Drawn word.
+    pub word: &'a str,
+    /// Difficulty tier of the pool the word was drawn from, if a target was
+    /// given.
+    pub difficulty: Option<Difficulty>,
+    /// Whether the word satisfies the constraints.
+    pub satisfied: bool,
+}
+
+/// Daily challenge scheduled at a date.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Daily<'a> {
+    /// Date of the challenge.
+    pub date: NaiveDate,
+    /// Word of the day.
+    pub word: String,
+    /// Target difficulty of the weekday, if any.
+    pub target: Option<Difficulty>,
+    /// Draw of the word, or `None` if the date is locked.
+    pub draw: Option<Draw<'a>>,
+}
+
+/// Scheduler of daily challenges.
+///
+/// Dates must be scheduled in increasing order, so that each word is checked
+/// against the previous days.
+#[derive(Clone, Debug)]
+pub struct Scheduler<'a> {
+    /// Words that can be drawn.
+    candidates: &'a [Candidate],
+    /// Indices of all candidates.
+    all: Vec<usize>,
+    /// Indices of the candidates of each difficulty tier.
+    pools: HashMap<Difficulty, Vec<usize>>,
+    /// Constraints between days.
+    constraints: Constraints,
+    /// Last date of each word.
+    word_dates: HashMap<String, NaiveDate>,
+    /// Last date of each lemma.
+    lemma_dates: HashMap<String, NaiveDate>,
+    /// Last date and its first letter.
+    previous: Option<(NaiveDate, char)>,
+}
+
+impl<'a> Scheduler<'a> {
+    /// Create a scheduler given the list of candidates (sorted by word) and the
+    /// constraints.
+    pub fn new(candidates: &'a [Candidate], constraints: Constraints) -> Self {
+        let mut pools: HashMap<Difficulty, Vec<usize>> = HashMap::new();
+
+        for (index, candidate) in candidates.iter().enumerate() {
+            if let Some(difficulty) = candidate.difficulty {
+                pools.entry(difficulty).or_default().push(index);
+            }
+        }
+
+        Self {
+            candidates,
+            all: (0..candidates.len()).collect(),
+            pools,
+            constraints,
+            word_dates: HashMap::new(),
+            lemma_dates: HashMap::new(),
+            previous: None,
+        }
+    }
+
+    /// Record a word scheduled at a given date (e.g. a locked word).
+    pub fn push<S>(&mut self, date: NaiveDate, word: S)
+    where
+        S: AsRef<str>,
+    {
+        let word = word.as_ref();
+
+        if let Some(candidate) = self.find(word) {
+            for lemma in candidate.lemmas.iter() {
+                self.lemma_dates.insert(lemma.to_owned(), date);
+            }
+        }
+
+        self.word_dates.insert(word.to_owned(), date);
+        self.previous = word.chars().next().map(|c| (date, c));
+    }
+
+    /// Draw a word for a given date and record it.
+    ///
+    /// Without target difficulty, the word is drawn from all candidates.
+    /// Otherwise, it is drawn from the candidates of the target tier, then of
+    /// the nearest tiers (the harder one first) if no candidate of the target
+    /// tier satisfies the constraints. The returned difficulty tells which
+    /// tier was used.
+    ///
+    /// In each pool, candidates are drawn at random until one satisfies the
+    /// constraints. Then, the candidates are scanned from a random starting
+    /// position. If no candidate satisfies the constraints, a random word of
+    /// the first non-empty pool is returned, marked as not satisfied.
+    pub fn draw<R>(
+        &mut self,
+        date: NaiveDate,
+        target: Option<Difficulty>,
+        rng: &mut R,
+    ) -> Option<Draw<'a>>
+    where
+        R: Rng + ?Sized,
+    {
+        let pools: Vec<(Option<Difficulty>, &[usize])> = match target {
+            None => vec![(None, &self.all)],
+            Some(target) => fallback_order(target)
+                .into_iter()
+                .filter_map(|difficulty| {
+                    let pool = self.pools.get(&difficulty)?;
+                    Some((Some(difficulty), pool.as_slice()))
+                })
+                .collect(),
+        };
+
+        // Random word of the first pool, if no word satisfies the constraints
+        let mut fallback: Option<Draw<'a>> = None;
+        let mut result: Option<Draw<'a>> = None;
+
+        for (difficulty, pool) in pools {
+            let (start, found) = self.draw_in(date, pool, rng);
+
+            if let Some(index) = found {
+                result = Some(Draw {
+                    word: &self.candidates[index].word,
+                    difficulty,
+                    satisfied: true,
+                });
+                break;
+            }
+
+            fallback.get_or_insert(Draw {
+                word: &self.candidates[start].word,
+                difficulty,
+                satisfied: false,
+            });
+        }
+
+        let result = result.or(fallback)?;
+
+        self.push(date, result.word);
+
+        Some(result)
+    }
+
+    /// Schedule the daily challenges of a range of dates.
+    ///
+    /// Days are always scheduled from the launch date, whatever the first date
+    /// of the range, so that the previous days constrain a date in the same
+    /// way and a date always maps to the same word. Locked dates keep their
+    /// word, and locked words before the launch date constrain the next days.
+    ///
+    /// Returns `None` if a date must be drawn but there is no candidate.
+    pub fn schedule(
+        &mut self,
+        launch_date: NaiveDate,
+        dates: RangeInclusive<NaiveDate>,
+        lock: &ScheduleLock,
+        curve: &DifficultyCurve,
+        key: u64,
+    ) -> Option<Vec<Daily<'a>>> {
+        for (date, word) in lock.iter().take_while(|(date, _)| **date < launch_date) {
+            self.push(*date, word);
+        }
+
+        let mut days = Vec::new();
+
+        for date in launch_date.iter_days() {
+            if date > *dates.end() {
+                break;
+            }
+
+            let target = curve.get(date.weekday());
+
+            let (word, draw) = match lock.get(&date) {
+                Some(word) => {
+                    self.push(date, word);
+                    (word.to_owned(), None)
+                },
+                None => {
+                    // Previous daily challenges should not be altered by a new
+                    // build. To preserve the sequence between builds, we use a
+                    // counter-based RNG whose counter depends only on the date.
+                    let mut rng = daily_rng(date, key);
+                    let draw = self.draw(date, target, &mut rng)?;
+                    (draw.word.to_owned(), Some(draw))
+                },
+            };
+
+            if dates.contains(&date) {
+                days.push(Daily {
+                    date,
+                    word,
+                    target,
+                    draw,
+                });
+            }
+        }
+
+        Some(days)
+    }
+
+    /// Draw the index of a candidate satisfying the constraints in a non-empty
+    /// pool, along with a random index of the pool.
+    fn draw_in<R>(&self, date: NaiveDate, pool: &[usize], rng: &mut R) -> (usize, Option<usize>)
+    where
+        R: Rng + ?Sized,
+    {
+        let n = pool.len();
+
+        let start = rng.gen_range(0..n);
+        let drawn = (0..MAX_DRAWS.min(n)).map(|_| rng.gen_range(0..n));
+        let scanned = (0..n).map(|i| (start + i) % n);
+
+        let found = drawn
+            .chain(scanned)
+            .map(|i| pool[i])
+            .find(|&index| self.check(date, index));
+
+        (pool[start], found)
+    }
+
+    /// Check if the candidate at a given index satisfies the constraints.
+    fn check(&self, date: NaiveDate, index: usize) -> bool {
+        let candidate = &self.candidates[index];
+
+        // Number of days since a previous date
+        let days = |previous: &NaiveDate| date.signed_duration_since(*previous).num_days();
+
+        let repeat_interval = self.constraints.repeat_interval as i64;
+        let lemma_interval = self.constraints.lemma_interval as i64;
+
+        if self
+            .word_dates
+            .get(&candidate.word)
+            .is_some_and(|previous| days(previous) < repeat_interval)
+        {
+            return false;
+        }
+
+        if candidate.lemmas.iter().any(|lemma| {
+            self.lemma_dates
+                .get(lemma)
+                .is_some_and(|previous| days(previous) < lemma_interval)
+        }) {
+            return false;
+        }
+
+        if self.constraints.distinct_first_letter {
+            if let Some((previous, c)) = &self.previous {
+                if days(previous) == 1 && candidate.word.starts_with(*c) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Find a candidate by word.
+    fn find(&self, word: &str) -> Option<&'a Candidate> {
+        self.candidates
+            .binary_search_by(|candidate| candidate.word.as_str().cmp(word))
+            .ok()
+            .map(|index| &self.candidates[index])
+    }
+}
+
+/// Order in which the difficulty tiers are tried for a target tier: the
+/// target first, then the nearest tiers, the harder one first.
+fn fallback_order(target: Difficulty) -> [Difficulty; 3] {
+    match target {
+        Difficulty::Easy => [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard],
+        Difficulty::Medium => [Difficulty::Medium, Difficulty::Hard, Difficulty::Easy],
+        Difficulty::Hard => [Difficulty::Hard, Difficulty::Medium, Difficulty::Easy],
+    }
+}
+
+/// Create the random number generator of a given date.
+///
+/// The counter is initialized with the number of days since the UNIX epoch,
+/// shifted to leave room for the draws of each day. Hence, the word of a date
+/// does not depend on the first generated date.
+pub fn daily_rng(date: NaiveDate, key: u64) -> SquaresRng {
+    let days_since_unix_epoch = date.signed_duration_since(NaiveDate::default()).num_days() as u64;
+
+    SquaresRng::new(days_since_unix_epoch << 32, key)
+}
+
+/// Lock of daily challenges that have already been published.
+///
+/// The lock file contains one `{date}\t{word}` line per daily challenge, sorted
+/// by date. Locked dates are never drawn again, so rebuilding with a different
+/// word list does not rewrite the past.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScheduleLock(BTreeMap<NaiveDate, String>);
+
+impl ScheduleLock {
+    /// Read a lock file.
+    /// A missing file is read as an empty lock.
+    pub fn read<P>(path: P) -> Result<Self, LockError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path).map_err(|err| LockError::new(0, err))?;
+
+        content.parse()
+    }
+
+    /// Write the lock file.
+    pub fn write<P>(&self, path: P) -> Result<(), std::io::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        for (date, word) in self.0.iter() {
+            let date = date.format("%Y-%m-%d");
+            writeln!(writer, "{date}\t{word}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the word locked at a given date.
+    pub fn get(&self, date: &NaiveDate) -> Option<&str> {
+        self.0.get(date).map(String::as_str)
+    }
+
+    /// Lock a word at a given date.
+    pub fn insert<S>(&mut self, date: NaiveDate, word: S)
+    where
+        S: AsRef<str>,
+    {
+        self.0.insert(date, word.as_ref().to_owned());
+    }
+
+    /// Iterate over locked dates and words, sorted by date.
+    pub fn iter(&self) -> impl Iterator<Item = (&NaiveDate, &str)> {
+        self.0.iter().map(|(date, word)| (date, word.as_str()))
+    }
+}
+
+impl std::str::FromStr for ScheduleLock {
+    type Err = LockError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lock = Self::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (date, word) = line
+                .split_once('\t')
+                .ok_or_else(|| LockError::new(line_number, "expected \"{date}\\t{word}\""))?;
+
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|err| LockError::new(line_number, format!("{err} in {date:?}")))?;
+
+            let word = word.trim();
+
+            if word.is_empty() {
+                return Err(LockError::new(line_number, "missing word"));
+            }
+
+            if lock.0.insert(date, word.to_owned()).is_some() {
+                return Err(LockError::new(
+                    line_number,
+                    format!("duplicate date {date}"),
+                ));
+            }
+        }
+
+        Ok(lock)
+    }
+}
+
+/// Lock file error.
+#[derive(Debug)]
+pub struct LockError {
+    line: usize,
+    message: String,
+}
+
+impl LockError {
+    pub fn new<S>(line: usize, message: S) -> Self
+    where
+        S: ToString,
+    {
+        Self {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for LockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line > 0 {
+            write!(
+                f,
+                "invalid lock file at line {}: {}",
+                self.line, self.message
+            )
+        } else {
+            write!(f, "invalid lock file: {}", self.message)
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn candidates(words: &[(&str, &str)]) -> Vec<Candidate> {
+        words
+            .iter()
+            .map(|(word, lemma)| Candidate {
+                word: word.to_string(),
+                lemmas: vec![lemma.to_string()],
+                difficulty: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn draw_deterministic() {
+        let candidates = candidates(&[("abricot", "abricot"), ("jardin", "jardin")]);
+        let draw = |seed| {
+            let mut rng = SquaresRng::new(0, seed);
+            let mut scheduler = Scheduler::new(&candidates, Constraints::default());
+            date("2024-01-01")
+                .iter_days()
+                .take(10)
+                .map(|date| scheduler.draw(date, None, &mut rng).unwrap().word)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(0x548c9decbce65297), draw(0x548c9decbce65297));
+    }
+
+    #[test]
+    fn draw_constraints() {
+        let candidates = candidates(&[
+            ("abricot", "abricot"),
+            ("chantant", "chanter"),
+            ("chanter", "chanter"),
+            ("dormir", "dormir"),
+            ("jardin", "jardin"),
+            ("jardiner", "jardiner"),
+            ("maison", "maison"),
+            ("voiture", "voiture"),
+        ]);
+        let constraints = Constraints {
+            repeat_interval: 4,
+            lemma_interval: 3,
+            distinct_first_letter: true,
+        };
+        let mut rng = SquaresRng::new(0, 0x548c9decbce65297);
+        let mut scheduler = Scheduler::new(&candidates, constraints);
+
+        let words: Vec<_> = date("2024-01-01")
+            .iter_days()
+            .take(100)
+            .map(|date| {
+                let draw = scheduler.draw(date, None, &mut rng).unwrap();
+                assert!(draw.satisfied);
+                draw.word
+            })
+            .collect();
+
+        for (i, word) in words.iter().enumerate() {
+            let next = &words[i + 1..words.len().min(i + 4)];
+            assert!(!next.contains(word));
+            if word.starts_with("chant") {
+                assert!(!next[..next.len().min(2)]
+                    .iter()
+                    .any(|w| w.starts_with("chant")));
+            }
+            if let Some(next) = next.first() {
+                assert_ne!(word.chars().next(), next.chars().next());
+            }
+        }
+    }
+
+    #[test]
+    fn schedule_windows() {
+        let candidates = candidates(&[
+            ("abricot", "abricot"),
+            ("chantant", "chanter"),
+            ("chanter", "chanter"),
+            ("dormir", "dormir"),
+            ("jardin", "jardin"),
+            ("maison", "maison"),
+        ]);
+        let constraints = Constraints {
+            repeat_interval: 3,
+            lemma_interval: 2,
+            distinct_first_letter: true,
+        };
+        let lock: ScheduleLock = "2024-01-05\tmaison\n".parse().unwrap();
+        let schedule = |start: &str, end: &str| {
+            let mut scheduler = Scheduler::new(&candidates, constraints.clone());
+            scheduler
+                .schedule(
+                    date("2024-01-01"),
+                    date(start)..=date(end),
+                    &lock,
+                    &DifficultyCurve::default(),
+                    0x548c9decbce65297,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|daily| (daily.date, daily.word))
+                .collect::<Vec<_>>()
+        };
+
+        // Windows starting on different days agree on the days they share
+        let first = schedule("2024-01-01", "2024-02-15");
+        let second = schedule("2024-01-20", "2024-03-01");
+        assert_eq!(first.len(), 46);
+        assert_eq!(second.first().unwrap().0, date("2024-01-20"));
+        assert_eq!(first[19..], second[..first.len() - 19]);
+        assert_eq!(first[4].1, "maison");
+    }
+
+    #[test]
+    fn draw_unsatisfiable() {
+        let candidates = candidates(&[("abricot", "abricot")]);
+        let constraints = Constraints {
+            repeat_interval: 2,
+            ..Constraints::default()
+        };
+        let mut rng = SquaresRng::new(0, 0x548c9decbce65297);
+        let mut scheduler = Scheduler::new(&candidates, constraints);
+
+        let draw = scheduler.draw(date("2024-01-01"), None, &mut rng).unwrap();
+        assert_eq!((draw.word, draw.satisfied), ("abricot", true));
+
+        let draw = scheduler.draw(date("2024-01-02"), None, &mut rng).unwrap();
+        assert_eq!((draw.word, draw.satisfied), ("abricot", false));
+    }
+
+    #[test]
+    fn draw_difficulty() {
+        let mut candidates = candidates(&[
+            ("abricot", "abricot"),
+            ("chanter", "chanter"),
+            ("dormir", "dormir"),
+            ("jardin", "jardin"),
+            ("maison", "maison"),
+        ]);
+        for (candidate, difficulty) in candidates.iter_mut().zip([
+            Difficulty::Easy,
+            Difficulty::Hard,
+            Difficulty::Easy,
+            Difficulty::Medium,
+            Difficulty::Easy,
+        ]) {
+            candidate.difficulty = Some(difficulty);
+        }
+        let constraints = Constraints {
+            repeat_interval: 10,
+            ..Constraints::default()
+        };
+        let draw = |seed| {
+            let mut rng = SquaresRng::new(0, seed);
+            let mut scheduler = Scheduler::new(&candidates, constraints.clone());
+            date("2024-01-01")
+                .iter_days()
+                .take(5)
+                .map(|date| {
+                    scheduler
+                        .draw(date, Some(Difficulty::Hard), &mut rng)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let draws = draw(0x548c9decbce65297);
+        assert_eq!(draws, draw(0x548c9decbce65297));
+
+        // The only hard word, then the medium one, then easy ones
+        let difficulties: Vec<_> = draws.iter().map(|draw| draw.difficulty).collect();
+        assert_eq!(
+            difficulties,
+            [
+                Some(Difficulty::Hard),
+                Some(Difficulty::Medium),
+                Some(Difficulty::Easy),
+                Some(Difficulty::Easy),
+                Some(Difficulty::Easy)
+            ]
+        );
+        assert_eq!(draws[0].word, "chanter");
+        assert_eq!(draws[1].word, "jardin");
+        assert!(draws.iter().all(|draw| draw.satisfied));
+    }
+
+    #[test]
+    fn parse_lock() {
+        let lock: ScheduleLock = "2024-01-02\tmaison\n2024-01-01\tjardin\n\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(lock.get(&date("2024-01-01")), Some("jardin"));
+        assert_eq!(lock.get(&date("2024-01-02")), Some("maison"));
+        assert_eq!(lock.get(&date("2024-01-03")), None);
+    }
+
+    #[test]
+    fn parse_invalid_lock() {
+        assert!("2024-01-01 maison".parse::<ScheduleLock>().is_err());
+        assert!("2024-13-01\tmaison".parse::<ScheduleLock>().is_err());
+        assert!("2024-01-01\t".parse::<ScheduleLock>().is_err());
+        assert!("2024-01-01\tmaison\n2024-01-01\tjardin"
+            .parse::<ScheduleLock>()
+            .is_err());
+    }
+}
@@ -0,0 +1,499 @@
+mod hunspell;
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+pub use hunspell::HunspellSource;
+use serde::Deserialize;
+
+use crate::{
+    lexique::{self, Cgram, ParseError, VecInfover},
+    transliterate::{TransliterationError, Transliterator},
+};
+
+/// Word entry yielded by a source.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Entry {
+    /// Normalized word (ascii lowercase), set by `read_sources`.
+    pub word: String,
+    /// Original spelling.
+    pub ortho: String,
+    /// Grammatical category, if known.
+    pub cgram: Option<Cgram>,
+    /// Lemma, if known.
+    pub lemme: Option<String>,
+    /// Frequency per million in movies, if known.
+    pub freqfilms2: Option<f64>,
+    /// Frequency per million in books, if known.
+    pub freqlivres: Option<f64>,
+    /// Verbal information.
+    pub infover: VecInfover,
+    /// Full Lexique record, if the source is Lexique.
+    pub record: Option<Box<lexique::Record>>,
+}
+
+impl Entry {
+    /// Create an entry from its original spelling, without metadata.
+    pub fn new<S>(ortho: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            ortho: ortho.as_ref().to_owned(),
+            ..Self::default()
+        }
+    }
+
+    /// Attach the full Lexique record.
+    fn with_record(self, record: lexique::Record) -> Self {
+        Self {
+            record: Some(Box::new(record)),
+            ..self
+        }
+    }
+}
+
+/// Source of words.
+pub trait WordSource {
+    /// Read all entries of the source.
+    fn entries(&self) -> anyhow::Result<Vec<Entry>>;
+
+    /// Read all entries of the source, and the rows that were skipped since
+    /// they cannot be parsed.
+    ///
+    /// In strict mode, the first row that cannot be parsed is an error. By
+    /// default, no row is skipped.
+    fn read(&self, _strict: bool) -> anyhow::Result<(Vec<Entry>, Vec<ParseError>)> {
+        Ok((self.entries()?, Vec::new()))
+    }
+}
+
+/// Lexique 3.83 database in TSV format.
+///
+/// Rows that cannot be parsed are skipped, unless in strict mode. Rows that
+/// have no grammatical category are kept with their full record, so that the
+/// `cgram` filter rejects them.
+#[derive(Clone, Debug)]
+pub struct LexiqueSource {
+    path: PathBuf,
+}
+
+impl LexiqueSource {
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_owned(),
+        }
+    }
+}
+
+impl WordSource for LexiqueSource {
+    fn entries(&self) -> anyhow::Result<Vec<Entry>> {
+        self.read(false).map(|(entries, _)| entries)
+    }
+
+    fn read(&self, strict: bool) -> anyhow::Result<(Vec<Entry>, Vec<ParseError>)> {
+        let mut entries = Vec::new();
+        let mut skipped = Vec::new();
+
+        for result in lexique::Reader::from_path(&self.path)? {
+            let record = match result {
+                Ok(record) => record,
+                Err(err) if strict => {
+                    let path = &self.path;
+                    return Err(anyhow::anyhow!("in {path:?}: {err}"));
+                },
+                Err(err) => {
+                    skipped.push(err);
+                    continue;
+                },
+            };
+
+            entries.push(
+                Entry {
+                    lemme: Some(record.lemme.to_owned()),
+                    cgram: record.cgram,
+                    freqfilms2: Some(record.freqfilms2),
+                    freqlivres: Some(record.freqlivres),
+                    infover: record.infover.to_owned(),
+                    ..Entry::new(&record.ortho)
+                }
+                .with_record(record),
+            );
+        }
+
+        Ok((entries, skipped))
+    }
+}
+
+/// Plain list of words, one word per line.
+///
+/// Empty lines and lines starting with `#` are skipped.
+#[derive(Clone, Debug)]
+pub struct WordListSource {
+    path: PathBuf,
+}
+
+impl WordListSource {
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_owned(),
+        }
+    }
+}
+
+impl WordSource for WordListSource {
+    fn entries(&self) -> anyhow::Result<Vec<Entry>> {
+        Ok(std::fs::read_to_string(&self.path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(Entry::new)
+            .collect())
+    }
+}
+
+/// List of words with their frequency per million, in TSV format
+/// (`{word}\t{frequency}`, without header).
+///
+/// The frequency is used for both movies and books. Rows whose frequency
+/// cannot be parsed are skipped, unless in strict mode.
+#[derive(Clone, Debug)]
+pub struct FrequencyListSource {
+    path: PathBuf,
+}
+
+impl FrequencyListSource {
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_owned(),
+        }
+    }
+}
+
+impl WordSource for FrequencyListSource {
+    fn entries(&self) -> anyhow::Result<Vec<Entry>> {
+        self.read(false).map(|(entries, _)| entries)
+    }
+
+    fn read(&self, strict: bool) -> anyhow::Result<(Vec<Entry>, Vec<ParseError>)> {
+        let (entries, mut skipped) = parse_frequency_list(&std::fs::read_to_string(&self.path)?);
+
+        if strict && !skipped.is_empty() {
+            let path = &self.path;
+            let err = skipped.swap_remove(0);
+            return Err(anyhow::anyhow!("in {path:?}: {err}"));
+        }
+
+        Ok((entries, skipped))
+    }
+}
+
+/// Parse a list of words with their frequency, and return the rows that cannot
+/// be parsed.
+fn parse_frequency_list(content: &str) -> (Vec<Entry>, Vec<ParseError>) {
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (word, frequency) = line.split_once('\t').unwrap_or((line, ""));
+        let frequency = frequency.trim();
+
+        match frequency.parse::<f64>() {
+            Ok(frequency) => entries.push(Entry {
+                freqfilms2: Some(frequency),
+                freqlivres: Some(frequency),
+                ..Entry::new(word.trim())
+            }),
+            Err(_) => skipped.push(
+                ParseError::new("frequency", frequency, frequency)
+                    .with_line(index as u64 + 1)
+                    .with_column("frequency")
+                    .with_ortho(word.trim()),
+            ),
+        }
+    }
+
+    (entries, skipped)
+}
+
+/// Configuration of a word source.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum SourceConfig {
+    /// Lexique 3.83 database.
+    Lexique { path: PathBuf },
+    /// Plain list of words.
+    Words { path: PathBuf },
+    /// List of words with their frequency.
+    Frequencies { path: PathBuf },
+    /// Hunspell dictionary.
+    Hunspell { dic: PathBuf, aff: PathBuf },
+}
+
+impl SourceConfig {
+    /// Open the source.
+    pub fn open(&self) -> Box<dyn WordSource> {
+        match self {
+            Self::Lexique { path } => Box::new(LexiqueSource::new(path)),
+            Self::Words { path } => Box::new(WordListSource::new(path)),
+            Self::Frequencies { path } => Box::new(FrequencyListSource::new(path)),
+            Self::Hunspell { dic, aff } => Box::new(HunspellSource::new(dic, aff)),
+        }
+    }
+}
+
+/// Method to merge several sources.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Merge {
+    /// Keep the entries of all sources.
+    #[default]
+    Union,
+    /// Keep the entries of the first source whose word is in all other
+    /// sources.
+    Intersection,
+}
+
+/// Entries left out while reading sources.
+#[derive(Debug, Default)]
+pub struct Skipped {
+    /// Words that cannot be transliterated.
+    pub words: Vec<TransliterationError>,
+    /// Rows that cannot be parsed.
+    pub rows: Vec<ParseError>,
+}
+
+impl Skipped {
+    /// Summary of the skipped rows: their number per kind of error (the
+    /// column, if known), followed by the first examples with their line
+    /// numbers.
+    pub fn row_summary(&self, num_examples: usize) -> String {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for err in self.rows.iter() {
+            let kind = match err.column() {
+                Some(column) => format!("invalid {column}"),
+                None => "invalid row".to_owned(),
+            };
+            *counts.entry(kind).or_default() += 1;
+        }
+
+        let mut summary = format!("{} rows cannot be parsed\n", self.rows.len());
+        for (kind, count) in counts {
+            summary.push_str(&format!("  {count:>6} {kind}\n"));
+        }
+        for err in self.rows.iter().take(num_examples) {
+            summary.push_str(&format!("  {err}\n"));
+        }
+        if self.rows.len() > num_examples {
+            summary.push_str("  ...\n");
+        }
+
+        summary
+    }
+}
+
+/// Read and merge entries from several sources.
+///
+/// Words are normalized with the transliterator. Entries that cannot be
+/// transliterated and rows that cannot be parsed are left out and returned,
+/// unless in strict mode, where the first row that cannot be parsed is an
+/// error.
+pub fn read_sources<'a, I>(
+    sources: I,
+    merge: Merge,
+    transliterator: &Transliterator,
+    strict: bool,
+) -> anyhow::Result<(Vec<Entry>, Skipped)>
+where
+    I: IntoIterator<Item = &'a dyn WordSource>,
+{
+    let mut skipped = Skipped::default();
+
+    let mut read = |source: &dyn WordSource| -> anyhow::Result<Vec<Entry>> {
+        let (entries, rows) = source.read(strict)?;
+        skipped.rows.extend(rows);
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| match transliterator.transliterate(&entry.ortho) {
+                Ok(word) => Some(Entry { word, ..entry }),
+                Err(err) => {
+                    skipped.words.push(err);
+                    None
+                },
+            })
+            .collect())
+    };
+
+    let mut sources = sources.into_iter();
+
+    let mut entries = match sources.next() {
+        Some(source) => read(source)?,
+        None => return Ok((Vec::new(), skipped)),
+    };
+
+    for source in sources {
+        let other = read(source)?;
+
+        match merge {
+            Merge::Union => entries.extend(other),
+            Merge::Intersection => {
+                // Rows of Lexique without grammatical category do not count
+                let words: std::collections::HashSet<String> = other
+                    .into_iter()
+                    .filter(|entry| entry.cgram.is_some() || entry.record.is_none())
+                    .map(|entry| entry.word)
+                    .collect();
+                entries.retain(|entry| words.contains(&entry.word));
+            },
+        }
+    }
+
+    Ok((entries, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Source of words given in memory.
+    struct MemorySource(Vec<&'static str>);
+
+    impl WordSource for MemorySource {
+        fn entries(&self) -> anyhow::Result<Vec<Entry>> {
+            Ok(self.0.iter().map(Entry::new).collect())
+        }
+    }
+
+    fn words(sources: &[&dyn WordSource], merge: Merge) -> Vec<String> {
+        let (entries, skipped) = read_sources(
+            sources.iter().copied(),
+            merge,
+            &Transliterator::default(),
+            false,
+        )
+        .unwrap();
+        assert!(skipped.words.is_empty());
+        entries.into_iter().map(|entry| entry.word).collect()
+    }
+
+    #[test]
+    fn merge_sources() {
+        let a = MemorySource(vec!["élève", "maison", "jardin"]);
+        let b = MemorySource(vec!["maison", "eleve", "voiture"]);
+
+        assert_eq!(
+            words(&[&a, &b], Merge::Union),
+            ["eleve", "maison", "jardin", "maison", "eleve", "voiture"]
+        );
+        assert_eq!(words(&[&a, &b], Merge::Intersection), ["eleve", "maison"]);
+    }
+
+    #[test]
+    fn transliteration_errors() {
+        let a = MemorySource(vec!["smørrebrød", "maison"]);
+        let (entries, skipped) = read_sources(
+            [&a as &dyn WordSource],
+            Merge::Union,
+            &Transliterator::default(),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            skipped.words,
+            [TransliterationError::UnknownCharacter(
+                "smørrebrød".to_owned(),
+                'ø'
+            )]
+        );
+    }
+
+    #[test]
+    fn row_summary() {
+        let database = "ortho\tcgram\naimer\tFOO\nmanger\tBAR\nfinir";
+        let skipped = Skipped {
+            words: Vec::new(),
+            rows: lexique::Reader::new(database.as_bytes())
+                .filter_map(Result::err)
+                .collect(),
+        };
+
+        assert_eq!(
+            skipped.row_summary(2),
+            "3 rows cannot be parsed\n\
+             \x20      2 invalid cgram\n\
+             \x20      1 invalid row\n\
+             \x20 line 2, column cgram: invalid cgram \"FOO\" in \"FOO\"\n\
+             \x20 line 3, column cgram: invalid cgram \"BAR\" in \"BAR\"\n\
+             \x20 ...\n"
+        );
+    }
+
+    #[test]
+    fn frequency_list_errors() {
+        let (entries, skipped) = parse_frequency_list("maison\t12.5\n\njardin\tfoo\nvoiture\n");
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].ortho, "maison");
+        assert_eq!(entries[0].freqfilms2, Some(12.5));
+        assert_eq!(
+            skipped.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "line 3, column frequency: invalid frequency \"foo\" in \"foo\"",
+                "line 4, column frequency: invalid frequency \"\" in \"\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_source_config() {
+        #[derive(Deserialize)]
+        struct Sources {
+            sources: Vec<SourceConfig>,
+        }
+
+        let config: Sources = toml::from_str(
+            r#"
+            [[sources]]
+            format = "lexique"
+            path = "Lexique383.tsv"
+
+            [[sources]]
+            format = "hunspell"
+            dic = "fr.dic"
+            aff = "fr.aff"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.sources,
+            [
+                SourceConfig::Lexique {
+                    path: "Lexique383.tsv".into()
+                },
+                SourceConfig::Hunspell {
+                    dic: "fr.dic".into(),
+                    aff: "fr.aff".into()
+                }
+            ]
+        );
+    }
+}
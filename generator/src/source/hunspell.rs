@@ -0,0 +1,475 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use super::{Entry, WordSource};
+
+/// Hunspell dictionary, made of a `.dic` and an `.aff` file.
+///
+/// Words of the `.dic` file are expanded with the prefix and suffix rules of
+/// the `.aff` file, including cross products and continuation classes. Each
+/// expanded word has its stem as lemma.
+///
+/// Only the affix options needed to list words are supported: `SET`, `FLAG`,
+/// `AF`, `PFX`, `SFX`, `NEEDAFFIX` and `FORBIDDENWORD`.
+#[derive(Clone, Debug)]
+pub struct HunspellSource {
+    dic: PathBuf,
+    aff: PathBuf,
+}
+
+impl HunspellSource {
+    pub fn new<P1, P2>(dic: P1, aff: P2) -> Self
+    where
+        P1: AsRef<Path>,
+        P2: AsRef<Path>,
+    {
+        Self {
+            dic: dic.as_ref().to_owned(),
+            aff: aff.as_ref().to_owned(),
+        }
+    }
+}
+
+impl WordSource for HunspellSource {
+    fn entries(&self) -> anyhow::Result<Vec<Entry>> {
+        let aff = std::fs::read(&self.aff)?;
+        let affixes = Affixes::parse(&decode(&aff, encoding(&aff)))?;
+
+        let dic = std::fs::read(&self.dic)?;
+        let dic = decode(&dic, affixes.encoding.as_deref());
+
+        let mut entries = Vec::new();
+
+        for (index, line) in dic.lines().enumerate() {
+            let line = line.trim();
+
+            // The first line is the approximate number of words
+            if line.is_empty() || (index == 0 && line.parse::<usize>().is_ok()) {
+                continue;
+            }
+
+            // Morphological fields follow the word and its flags
+            let field = line.split_whitespace().next().unwrap_or_default();
+            let (stem, flags) = field.split_once('/').unwrap_or((field, ""));
+            let flags = affixes.parse_flags(flags);
+
+            for word in affixes.expand(stem, &flags) {
+                entries.push(Entry {
+                    lemme: Some(stem.to_owned()),
+                    ..Entry::new(word)
+                });
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Affix rules of an `.aff` file.
+#[derive(Clone, Debug, Default)]
+struct Affixes {
+    /// Character encoding (`SET`).
+    encoding: Option<String>,
+    /// Flag type (`FLAG`).
+    flag_type: FlagType,
+    /// Flag aliases (`AF`).
+    aliases: Vec<Vec<String>>,
+    /// Prefix classes (`PFX`).
+    prefixes: HashMap<String, AffixClass>,
+    /// Suffix classes (`SFX`).
+    suffixes: HashMap<String, AffixClass>,
+    /// Flag of words that need an affix (`NEEDAFFIX`).
+    need_affix: Option<String>,
+    /// Flag of forbidden words (`FORBIDDENWORD`).
+    forbidden_word: Option<String>,
+}
+
+/// Flag type.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum FlagType {
+    /// One character per flag.
+    #[default]
+    Char,
+    /// Two characters per flag.
+    Long,
+    /// Comma-separated numbers.
+    Num,
+}
+
+/// Prefix or suffix class.
+#[derive(Clone, Debug, Default)]
+struct AffixClass {
+    /// Whether the class can be combined with the other kind of affix.
+    cross_product: bool,
+    /// Rules of the class.
+    rules: Vec<AffixRule>,
+}
+
+/// Prefix or suffix rule.
+#[derive(Clone, Debug, Default)]
+struct AffixRule {
+    /// Characters to remove from the stem.
+    strip: String,
+    /// Characters to add to the stem.
+    add: String,
+    /// Continuation flags.
+    flags: Vec<String>,
+    /// Condition on the stem.
+    condition: Vec<CharClass>,
+}
+
+/// Character class of a condition.
+#[derive(Clone, Debug, PartialEq)]
+enum CharClass {
+    /// Any character (`.`).
+    Any,
+    /// Set of characters (`[abc]`, or `[^abc]` if negated).
+    Set(Vec<char>, bool),
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Set(chars, negated) => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+impl Affixes {
+    /// Parse the content of an `.aff` file.
+    fn parse(content: &str) -> anyhow::Result<Self> {
+        let mut affixes = Self::default();
+
+        // Number of remaining rules in the current class
+        let mut remaining: usize = 0;
+
+        for (index, line) in content.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let line_number = index + 1;
+            let invalid = || anyhow::anyhow!("invalid affix line {line_number}: {line:?}");
+
+            match fields.as_slice() {
+                ["SET", encoding, ..] => affixes.encoding = Some(encoding.to_string()),
+                ["FLAG", flag_type, ..] => {
+                    affixes.flag_type = match *flag_type {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Num,
+                        _ => FlagType::Char,
+                    }
+                },
+                ["AF", flags, ..] if flags.parse::<usize>().is_err() => {
+                    let flags = affixes.parse_flags(flags);
+                    affixes.aliases.push(flags);
+                },
+                ["NEEDAFFIX", flag, ..] => affixes.need_affix = Some(flag.to_string()),
+                ["FORBIDDENWORD", flag, ..] => affixes.forbidden_word = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, cross_product, count] if remaining == 0 => {
+                    remaining = count.parse().map_err(|_| invalid())?;
+                    affixes.classes(kind).insert(
+                        flag.to_string(),
+                        AffixClass {
+                            cross_product: *cross_product == "Y",
+                            rules: Vec::new(),
+                        },
+                    );
+                },
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let (add, flags) = add.split_once('/').unwrap_or((add, ""));
+                    let rule = AffixRule {
+                        strip: if *strip == "0" { "" } else { strip }.to_owned(),
+                        add: if add == "0" { "" } else { add }.to_owned(),
+                        flags: affixes.parse_flags(flags),
+                        condition: parse_condition(rest.first().unwrap_or(&"."))
+                            .ok_or_else(invalid)?,
+                    };
+                    affixes
+                        .classes(kind)
+                        .get_mut(*flag)
+                        .ok_or_else(invalid)?
+                        .rules
+                        .push(rule);
+                    remaining = remaining.saturating_sub(1);
+                },
+                _ => {},
+            }
+        }
+
+        Ok(affixes)
+    }
+
+    /// Get the prefix (`PFX`) or suffix (`SFX`) classes.
+    fn classes(&mut self, kind: &str) -> &mut HashMap<String, AffixClass> {
+        match kind {
+            "PFX" => &mut self.prefixes,
+            _ => &mut self.suffixes,
+        }
+    }
+
+    /// Parse a list of flags, or an alias if `AF` is used.
+    fn parse_flags(&self, s: &str) -> Vec<String> {
+        if !self.aliases.is_empty() {
+            if let Ok(index) = s.parse::<usize>() {
+                return self
+                    .aliases
+                    .get(index.wrapping_sub(1))
+                    .cloned()
+                    .unwrap_or_default();
+            }
+        }
+
+        match self.flag_type {
+            FlagType::Char => s.chars().map(String::from).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = s.chars().collect();
+                chars.chunks(2).map(|c| c.iter().collect()).collect()
+            },
+            FlagType::Num => s
+                .split(',')
+                .filter(|flag| !flag.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        }
+    }
+
+    /// List all words derived from a stem and its flags.
+    fn expand(&self, stem: &str, flags: &[String]) -> BTreeSet<String> {
+        let mut words = BTreeSet::new();
+
+        let has = |flags: &[String], flag: &Option<String>| {
+            flag.as_ref().is_some_and(|flag| flags.contains(flag))
+        };
+
+        if has(flags, &self.forbidden_word) {
+            return words;
+        }
+
+        if !has(flags, &self.need_affix) {
+            words.insert(stem.to_owned());
+        }
+
+        for flag in flags {
+            if let Some(class) = self.suffixes.get(flag) {
+                for rule in class.rules.iter() {
+                    let Some(word) = apply_suffix(rule, stem) else {
+                        continue;
+                    };
+
+                    // Twofold suffixes
+                    for continuation in rule.flags.iter() {
+                        for rule in self.suffix_rules(continuation) {
+                            words.extend(apply_suffix(rule, &word));
+                        }
+                    }
+
+                    // Prefixes combined with the suffix
+                    if class.cross_product {
+                        for prefix_flag in flags {
+                            let Some(prefix_class) = self.prefixes.get(prefix_flag) else {
+                                continue;
+                            };
+                            if prefix_class.cross_product {
+                                for rule in prefix_class.rules.iter() {
+                                    words.extend(apply_prefix(rule, &word));
+                                }
+                            }
+                        }
+                    }
+
+                    if !has(&rule.flags, &self.need_affix) {
+                        words.insert(word);
+                    }
+                }
+            }
+
+            if let Some(class) = self.prefixes.get(flag) {
+                for rule in class.rules.iter() {
+                    if has(&rule.flags, &self.need_affix) {
+                        continue;
+                    }
+                    words.extend(apply_prefix(rule, stem));
+                }
+            }
+        }
+
+        words
+    }
+
+    /// Get the rules of a suffix class.
+    fn suffix_rules(&self, flag: &str) -> impl Iterator<Item = &AffixRule> {
+        self.suffixes
+            .get(flag)
+            .into_iter()
+            .flat_map(|class| class.rules.iter())
+    }
+}
+
+/// Apply a suffix rule to a word, if the condition matches.
+fn apply_suffix(rule: &AffixRule, word: &str) -> Option<String> {
+    let chars: Vec<char> = word.chars().collect();
+
+    if !word.ends_with(&rule.strip) || chars.len() < rule.condition.len() {
+        return None;
+    }
+
+    let end = &chars[chars.len() - rule.condition.len()..];
+
+    if !rule.condition.iter().zip(end).all(|(cc, &c)| cc.matches(c)) {
+        return None;
+    }
+
+    let stem = &word[..word.len() - rule.strip.len()];
+
+    Some(format!("{stem}{}", rule.add))
+}
+
+/// Apply a prefix rule to a word, if the condition matches.
+fn apply_prefix(rule: &AffixRule, word: &str) -> Option<String> {
+    let chars: Vec<char> = word.chars().collect();
+
+    if !word.starts_with(&rule.strip) || chars.len() < rule.condition.len() {
+        return None;
+    }
+
+    if !rule
+        .condition
+        .iter()
+        .zip(&chars)
+        .all(|(cc, &c)| cc.matches(c))
+    {
+        return None;
+    }
+
+    let stem = &word[rule.strip.len()..];
+
+    Some(format!("{}{stem}", rule.add))
+}
+
+/// Parse the condition of an affix rule (e.g. `[^aeiou]y`).
+fn parse_condition(s: &str) -> Option<Vec<CharClass>> {
+    if s == "." {
+        return Some(Vec::new());
+    }
+
+    let mut condition = Vec::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        condition.push(match c {
+            '.' => CharClass::Any,
+            '[' => {
+                let mut set = Vec::new();
+                let mut negated = false;
+                loop {
+                    match chars.next()? {
+                        ']' => break,
+                        '^' if set.is_empty() && !negated => negated = true,
+                        c => set.push(c),
+                    }
+                }
+                CharClass::Set(set, negated)
+            },
+            c => CharClass::Set(vec![c], false),
+        });
+    }
+
+    Some(condition)
+}
+
+/// Get the encoding declared in an `.aff` file.
+fn encoding(aff: &[u8]) -> Option<&str> {
+    aff.split(|&b| b == b'\n')
+        .filter_map(|line| std::str::from_utf8(line).ok())
+        .find_map(|line| line.trim().strip_prefix("SET "))
+        .map(str::trim)
+}
+
+/// Decode a file given its encoding (UTF-8 by default).
+fn decode(bytes: &[u8], encoding: Option<&str>) -> String {
+    match encoding {
+        Some("ISO8859-1") => bytes.iter().map(|&b| b as char).collect(),
+        Some("ISO8859-15") => bytes
+            .iter()
+            .map(|&b| match b {
+                0xa4 => '€',
+                0xa6 => 'Š',
+                0xa8 => 'š',
+                0xb4 => 'Ž',
+                0xb8 => 'ž',
+                0xbc => 'Œ',
+                0xbd => 'œ',
+                0xbe => 'Ÿ',
+                b => b as char,
+            })
+            .collect(),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+FLAG long
+NEEDAFFIX ()
+
+SFX S. Y 1
+SFX S. 0 s .
+
+SFX F. Y 2
+SFX F. 0 e/S. [^e]
+SFX F. 0 s .
+
+SFX Er Y 3
+SFX Er er ant er
+SFX Er er é/F. er
+SFX Er er ons/() er
+
+PFX Re Y 1
+PFX Re 0 re [^aeiou]
+";
+
+    fn expand(word: &str, flags: &str) -> Vec<String> {
+        let affixes = Affixes::parse(AFF).unwrap();
+        let flags = affixes.parse_flags(flags);
+        affixes.expand(word, &flags).into_iter().collect()
+    }
+
+    #[test]
+    fn expand_suffixes() {
+        assert_eq!(expand("maison", "S."), ["maison", "maisons"]);
+        assert_eq!(
+            expand("petit", "F."),
+            ["petit", "petite", "petites", "petits"]
+        );
+        assert_eq!(
+            expand("chanter", "Er"),
+            ["chantant", "chanter", "chanté", "chantée", "chantés"]
+        );
+    }
+
+    #[test]
+    fn expand_cross_product() {
+        assert_eq!(
+            expand("lire", "ReS."),
+            ["lire", "lires", "relire", "relires"]
+        );
+    }
+
+    #[test]
+    fn parse_conditions() {
+        assert_eq!(parse_condition("."), Some(vec![]));
+        assert_eq!(
+            parse_condition("[^ae]y"),
+            Some(vec![
+                CharClass::Set(vec!['a', 'e'], true),
+                CharClass::Set(vec!['y'], false)
+            ])
+        );
+        assert_eq!(parse_condition("[ab"), None);
+    }
+}
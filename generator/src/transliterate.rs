@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Ligatures and their expansions.
+const LIGATURES: [(char, &str); 11] = [
+    ('æ', "ae"),
+    ('œ', "oe"),
+    ('ĳ', "ij"),
+    ('ß', "ss"),
+    ('ﬀ', "ff"),
+    ('ﬁ', "fi"),
+    ('ﬂ', "fl"),
+    ('ﬃ', "ffi"),
+    ('ﬄ', "ffl"),
+    ('ﬅ', "st"),
+    ('ﬆ', "st"),
+];
+
+/// Policy for ligatures, whose expansion changes the word length.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LigaturePolicy {
+    /// Expand ligatures (e.g. "œ" to "oe").
+    #[default]
+    Expand,
+    /// Reject words containing ligatures.
+    Reject,
+}
+
+/// Transliteration of words to ascii lowercase.
+///
+/// Characters are first looked up in the user mapping. Otherwise, ligatures
+/// are handled according to the policy, and other characters are decomposed
+/// (NFD) to remove their diacritics.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transliterator {
+    /// User mapping, which takes precedence over the default rules.
+    mapping: BTreeMap<char, String>,
+    /// Policy for ligatures.
+    ligatures: LigaturePolicy,
+}
+
+impl Transliterator {
+    pub fn new(mapping: BTreeMap<char, String>, ligatures: LigaturePolicy) -> Self {
+        Self { mapping, ligatures }
+    }
+
+    /// Convert a word to lowercase and remove diacritics.
+    pub fn transliterate<S>(&self, word: S) -> Result<String, TransliterationError>
+    where
+        S: AsRef<str>,
+    {
+        let word = word.as_ref();
+        let mut result = String::with_capacity(word.len());
+
+        for c in word.to_lowercase().chars() {
+            if let Some(s) = self.mapping.get(&c) {
+                result.push_str(s);
+            } else if c.is_ascii() {
+                result.push(c);
+            } else if let Some((_, s)) = LIGATURES.iter().find(|(l, _)| *l == c) {
+                match self.ligatures {
+                    LigaturePolicy::Expand => result.push_str(s),
+                    LigaturePolicy::Reject => {
+                        return Err(TransliterationError::Ligature(word.to_owned(), c))
+                    },
+                }
+            } else {
+                for d in c.nfd().filter(|&d| !is_combining_mark(d)) {
+                    if !d.is_ascii() {
+                        return Err(TransliterationError::UnknownCharacter(word.to_owned(), c));
+                    }
+                    result.push(d);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Transliteration error.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransliterationError {
+    /// The word contains a character that cannot be transliterated.
+    UnknownCharacter(String, char),
+    /// The word contains a ligature, which is rejected by the policy.
+    Ligature(String, char),
+}
+
+impl std::fmt::Display for TransliterationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCharacter(word, c) => {
+                write!(f, "unknown non-ascii character '{c}' in {word:?}")
+            },
+            Self::Ligature(word, c) => write!(f, "rejected ligature '{c}' in {word:?}"),
+        }
+    }
+}
+
+impl std::error::Error for TransliterationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_diacritics() {
+        let t = Transliterator::default();
+        assert_eq!(t.transliterate("Élève").unwrap(), "eleve");
+        assert_eq!(t.transliterate("naïveté").unwrap(), "naivete");
+        assert_eq!(t.transliterate("garçon").unwrap(), "garcon");
+        assert_eq!(t.transliterate("aujourd'hui").unwrap(), "aujourd'hui");
+        // Decomposed form
+        assert_eq!(t.transliterate("e\u{301}te\u{301}").unwrap(), "ete");
+    }
+
+    #[test]
+    fn ligatures() {
+        let t = Transliterator::default();
+        assert_eq!(t.transliterate("cœur").unwrap(), "coeur");
+        assert_eq!(t.transliterate("Œuvre").unwrap(), "oeuvre");
+
+        let t = Transliterator::new(BTreeMap::new(), LigaturePolicy::Reject);
+        assert_eq!(
+            t.transliterate("cœur"),
+            Err(TransliterationError::Ligature("cœur".to_owned(), 'œ'))
+        );
+    }
+
+    #[test]
+    fn mapping() {
+        let t = Transliterator::default();
+        assert_eq!(
+            t.transliterate("smørrebrød"),
+            Err(TransliterationError::UnknownCharacter(
+                "smørrebrød".to_owned(),
+                'ø'
+            ))
+        );
+
+        let mapping = BTreeMap::from([('ø', "o".to_owned()), ('œ', "o".to_owned())]);
+        let t = Transliterator::new(mapping, LigaturePolicy::Reject);
+        assert_eq!(t.transliterate("smørrebrød").unwrap(), "smorrebrod");
+        assert_eq!(t.transliterate("cœur").unwrap(), "cour");
+    }
+}
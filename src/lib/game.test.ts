@@ -1,5 +1,6 @@
-import { describe, expect, it } from 'vitest';
+import { afterEach, describe, expect, it, vi } from 'vitest';
 import {
+  fetchRandomChallenge,
   getBoardContent,
   getBoardState,
   getCurrentGuessState,
@@ -111,3 +112,62 @@ describe('game library', () => {
     });
   });
 });
+
+describe('random challenge', () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  /** Stub `fetch` with the contents of some files, other files are missing. */
+  function stubFetch(files: Record<string, string>) {
+    const fetch = vi.fn(async (url: string) => {
+      const body = files[url];
+      return {
+        ok: body !== undefined,
+        status: body === undefined ? 404 : 200,
+        text: async () => body ?? '',
+      } as Response;
+    });
+    vi.stubGlobal('fetch', fetch);
+    return fetch;
+  }
+
+  it('draws a challenge of a tier', async () => {
+    stubFetch({
+      '/challenge-hard.txt': '2\n',
+      '/challenge/2.txt': 'maison\nmaison\nhard\n',
+    });
+    const challenge = await fetchRandomChallenge('hard');
+    expect(challenge).toStrictEqual({
+      id: '2',
+      solution: 'maison',
+      display: 'maison',
+      difficulty: 'hard',
+    });
+  });
+
+  it('falls back to any challenge without challenge of a tier', async () => {
+    const fetch = stubFetch({
+      '/challenge-hard.txt': '\n',
+      '/challenge-count.txt': '1\n',
+      '/challenge/1.txt': 'jardin\njardin\neasy\n',
+    });
+    const challenge = await fetchRandomChallenge('hard');
+    expect(challenge.solution).toBe('jardin');
+    expect(fetch).not.toHaveBeenCalledWith('/challenge/.txt');
+  });
+
+  it('falls back to any challenge without tier file', async () => {
+    stubFetch({
+      '/challenge-count.txt': '1\n',
+      '/challenge/1.txt': 'jardin\n',
+    });
+    const challenge = await fetchRandomChallenge('easy');
+    expect(challenge.solution).toBe('jardin');
+  });
+
+  it('fails without challenge count', async () => {
+    stubFetch({});
+    await expect(fetchRandomChallenge()).rejects.toThrow();
+  });
+});
@@ -1,12 +1,19 @@
 import { todayISOString } from './date';
 import { fetchLines } from './fetch';
 
+/**
+ * Difficulty tier of a challenge.
+ */
+export type Difficulty = 'easy' | 'medium' | 'hard';
+
 /**
  * Challenge data.
  */
 export type Challenge = {
   id: string;
   solution: string;
+  display: string;
+  difficulty?: Difficulty;
 };
 
 /**
@@ -18,8 +25,18 @@ export type Challenge = {
  */
 export async function fetchChallenge(id: string): Promise<Challenge> {
   const response = await fetch(`/challenge/${id}.txt`);
-  const solution = (await response.text()).trim();
-  const challenge = { id, solution };
+  const [solution = '', display, difficulty] = (await response.text())
+    .trim()
+    .split(/\r?\n/u);
+  const challenge: Challenge = {
+    id,
+    solution,
+    display: display?.trim() || solution,
+  };
+  const tier = difficulty?.trim();
+  if (isDifficulty(tier)) {
+    challenge.difficulty = tier;
+  }
   if (import.meta.env.DEV) {
     console.log(`fetchChallenge: ${JSON.stringify(challenge)}`);
   }
@@ -39,15 +56,47 @@ export async function fetchDailyChallenge(): Promise<Challenge> {
 /**
  * Fetch a random challenge.
  *
+ * @param difficulty Difficulty tier of the challenge (any tier by default).
+ *
  * @returns Challenge data.
  */
-export async function fetchRandomChallenge(): Promise<Challenge> {
+export async function fetchRandomChallenge(
+  difficulty?: Difficulty,
+): Promise<Challenge> {
+  if (difficulty) {
+    const response = await fetch(`/challenge-${difficulty}.txt`);
+    const indices = response.ok
+      ? (await response.text())
+          .split(/\r?\n/u)
+          .map((line) => line.trim())
+          .filter((line) => line !== '')
+      : [];
+    // Without challenge of this tier, fall back to any challenge
+    if (indices.length > 0) {
+      const index = indices[Math.floor(Math.random() * indices.length)];
+      return fetchChallenge(index);
+    }
+  }
   const response = await fetch(`/challenge-count.txt`);
+  if (!response.ok) {
+    throw new Error(`Cannot fetch the challenge count: ${response.status}`);
+  }
   const count = Number((await response.text()).trim());
   const index = 1 + Math.floor(Math.random() * count);
   return fetchChallenge(index.toString());
 }
 
+/**
+ * Check whether a string is a difficulty tier.
+ *
+ * @param value String to check.
+ *
+ * @returns `true` if the string is a difficulty tier.
+ */
+export function isDifficulty(value?: string): value is Difficulty {
+  return value === 'easy' || value === 'medium' || value === 'hard';
+}
+
 /**
  * Fetch a dictionary given its ID.
  *
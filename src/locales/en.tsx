@@ -12,6 +12,7 @@ export default {
     tooShort: 'This word is too short',
     notInDictionary: 'This word is not in the dictionary',
     alreadyTried: 'This word has already been tried',
+    solution: (props: { word: string }) => `The word was ${props.word}`,
   },
   settings: {
     title: 'Settings',
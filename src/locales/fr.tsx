@@ -12,6 +12,7 @@ export default {
     tooShort: 'Ce mot est trop court',
     notInDictionary: 'Ce mot est absent du dictionnaire',
     alreadyTried: 'Ce mot a déjà été essayé',
+    solution: (props: { word: string }) => `Le mot était ${props.word}`,
   },
   settings: {
     title: 'Paramètres',
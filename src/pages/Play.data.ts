@@ -4,6 +4,7 @@ import {
   fetchChallenge,
   fetchDailyChallenge,
   fetchRandomChallenge,
+  isDifficulty,
 } from '../lib/game';
 
 /**
@@ -25,6 +26,11 @@ export const loadDailyChallenge: RouteLoadFunc<Promise<Challenge>> = () => {
 /**
  * Load a random challenge.
  */
-export const loadRandomChallenge: RouteLoadFunc<Promise<Challenge>> = () => {
-  return fetchRandomChallenge();
+export const loadRandomChallenge: RouteLoadFunc<Promise<Challenge>> = ({
+  params,
+}) => {
+  const difficulty = params.difficulty;
+  return fetchRandomChallenge(
+    isDifficulty(difficulty) ? difficulty : undefined,
+  );
 };
@@ -1,5 +1,6 @@
 import {
   Component,
+  Show,
   Suspense,
   batch,
   createEffect,
@@ -176,6 +177,11 @@ const Play: Component<{ data: Promise<Challenge> }> = (props) => {
           </Suspense>
         </div>
       </div>
+      <Show when={isGameOver()}>
+        <div class={styles('solution')}>
+          {translate('game.solution', { word: challenge()?.display })}
+        </div>
+      </Show>
       <div class={styles('footer')}>
         <Keyboard
           layout={app.keyboardLayout ?? []}
@@ -20,6 +20,11 @@ export default [
     component: lazy(() => import('./pages/Play')),
     load: loadRandomChallenge,
   },
+  {
+    path: '/play/random/:difficulty',
+    component: lazy(() => import('./pages/Play')),
+    load: loadRandomChallenge,
+  },
   {
     path: '/play/:id',
     component: lazy(() => import('./pages/Play')),